@@ -13,16 +13,18 @@ use protocol_ftp_client::*;
 fn get_reply(stream:&mut TcpStream, rx_buff: &mut [u8], receiver: FtpReceiver) -> FtpTransmitter {
   let mut opt_transmitter = None;
   let mut opt_receiver = Some(receiver);
-  let mut total_size = 0;
+  // the reply might have been already received along with the previous one
+  let mut sz = 0;
   while opt_receiver.is_some() {
-    let sz = stream.read(rx_buff).unwrap();
-    total_size = total_size + sz;
     let ftp_receiver = opt_receiver.take().unwrap();
-    match ftp_receiver.try_advance(&rx_buff[0 .. total_size]) {
+    match ftp_receiver.try_advance(&rx_buff[0 .. sz]) {
       Ok(transmitter)   => { opt_transmitter = Some(transmitter) }
       Err(mut receiver) => {
         match receiver.take_error() {
-          Some(FtpError::NotEnoughData) => { opt_receiver = Some(receiver) }
+          Some(FtpError::NotEnoughData) => {
+            sz = stream.read(rx_buff).unwrap();
+            opt_receiver = Some(receiver)
+          }
           Some(e)  => { panic!(format!("Got unexpected error {}", e )) }
           _ => {panic!("no advance nor error?")}
         };
@@ -68,54 +70,54 @@ fn main() {
   let mut transmitter = get_reply(&mut stream, &mut rx_buff, ftp_receiver);
   println!("connected to {}:{}", host, port);
 
-  ftp_receiver = transmitter.send_login(&mut tx_buff, &mut tx_count, username);
+  ftp_receiver = transmitter.send_login(&mut tx_buff, &mut tx_count, username).ok().unwrap();
   let _ = stream.write_all(&tx_buff[0 .. tx_count]).unwrap();
   println!("login sent...");
 
   transmitter = get_reply(&mut stream, &mut rx_buff, ftp_receiver);
   println!("expecting password...");
 
-  ftp_receiver = transmitter.send_password(&mut tx_buff, &mut tx_count, password);
+  ftp_receiver = transmitter.send_password(&mut tx_buff, &mut tx_count, password).ok().unwrap();
   let _ = stream.write_all(&tx_buff[0 .. tx_count]).unwrap();
   println!("password sent...");
 
   transmitter = get_reply(&mut stream, &mut rx_buff, ftp_receiver);
   println!("logged in...");
 
-  ftp_receiver = transmitter.send_system_req(&mut tx_buff, &mut tx_count);
+  ftp_receiver = transmitter.send_system_req(&mut tx_buff, &mut tx_count).ok().unwrap();
   let _ = stream.write_all(&tx_buff[0 .. tx_count]).unwrap();
   transmitter = get_reply(&mut stream, &mut rx_buff, ftp_receiver);
   {
-    let (system, subtype) = transmitter.get_system().clone();
+    let (system, subtype) = transmitter.get_system().unwrap();
     println!("remote system {} / {}", system, subtype);
   }
 
-  ftp_receiver = transmitter.send_cwd_req(&mut tx_buff, &mut tx_count, &remote_path);
+  ftp_receiver = transmitter.send_cwd_req(&mut tx_buff, &mut tx_count, &remote_path).ok().unwrap();
   let _ = stream.write_all(&tx_buff[0 .. tx_count]).unwrap();
   transmitter = get_reply(&mut stream, &mut rx_buff, ftp_receiver);
   println!("cwd to {}", remote_path);
 
-  ftp_receiver = transmitter.send_pwd_req(&mut tx_buff, &mut tx_count);
+  ftp_receiver = transmitter.send_pwd_req(&mut tx_buff, &mut tx_count).ok().unwrap();
   let _ = stream.write_all(&tx_buff[0 .. tx_count]).unwrap();
   transmitter = get_reply(&mut stream, &mut rx_buff, ftp_receiver);
-  println!("changed remote directory is {}", transmitter.get_wd());
+  println!("changed remote directory is {}", transmitter.get_wd().unwrap());
 
-  ftp_receiver = transmitter.send_type_req(&mut tx_buff, &mut tx_count, DataMode::Binary);
+  ftp_receiver = transmitter.send_type_req(&mut tx_buff, &mut tx_count, DataMode::Binary).ok().unwrap();
   let _ = stream.write_all(&tx_buff[0 .. tx_count]).unwrap();
   transmitter = get_reply(&mut stream, &mut rx_buff, ftp_receiver);
   println!("switched to binary mode");
 
   let mut data_stream = {
-    ftp_receiver = transmitter.send_pasv_req(&mut tx_buff, &mut tx_count);
+    ftp_receiver = transmitter.send_pasv_req(&mut tx_buff, &mut tx_count).ok().unwrap();
     let _ = stream.write_all(&tx_buff[0 .. tx_count]).unwrap();
     transmitter = get_reply(&mut stream, &mut rx_buff, ftp_receiver);
-    let (addr, port) = transmitter.take_endpoint().clone();
-    println!("confirmed passive connection on {}:{}", addr, port);
-    TcpStream::connect((addr, port)).unwrap()
+    let addr = transmitter.take_endpoint().unwrap();
+    println!("confirmed passive connection on {}", addr);
+    TcpStream::connect(addr).unwrap()
   };
   println!("passive connection opened");
 
-  ftp_receiver = transmitter.send_get_req(&mut tx_buff, &mut tx_count, ftp_url.path());
+  ftp_receiver = transmitter.send_get_req(&mut tx_buff, &mut tx_count, ftp_url.path()).ok().unwrap();
 
   let _ = stream.write_all(&tx_buff[0 .. tx_count]).unwrap();
   transmitter = get_reply(&mut stream, &mut rx_buff, ftp_receiver);
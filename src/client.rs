@@ -0,0 +1,394 @@
+//! Event-driven (sans-IO) client: the received bytes are fed via `handle_input`, and the
+//! outcomes are polled via `poll_event`, while the commands to be sent are polled via
+//! `poll_transmit`. The client performs no I/O itself, so it can be driven by any event
+//! loop (mio, tokio etc.).
+//!
+//! ```
+//! use protocol_ftp_client::client::*;
+//!
+//! let mut client = FtpClient::new();
+//! client.handle_input("220 Service ready for new user.\r\n".as_bytes());
+//! assert!(client.poll_event().is_some());
+//!
+//! client.login("anonymous", "anonymous@nowhere.com").unwrap();
+//! assert_eq!(client.poll_transmit(), Some("USER anonymous\r\n".as_bytes().to_vec()));
+//!
+//! client.handle_input("331 Please specify the password.\r\n".as_bytes());
+//! assert_eq!(client.poll_transmit(), Some("PASS anonymous@nowhere.com\r\n".as_bytes().to_vec()));
+//!
+//! client.handle_input("230 Login successful.\r\n".as_bytes());
+//! assert_eq!(client.poll_event(), Some(Event::LoggedIn));
+//! ```
+
+use std::collections::VecDeque;
+use std::net::{IpAddr, Ipv4Addr, SocketAddr};
+
+use super::{DataMode, FtpError, FtpReceiver, FtpTransmitter, Reply};
+use super::{DATA_CONNECTION_OPENED, OPENNING_DATA_CONNECTION, PASSIVE_MODE, EXTENDED_PASSIVE_MODE, LOGGED_IN,
+            PASSWORD_EXPECTED};
+
+const INITIAL_BUFFER_SIZE: usize = 1024;
+
+/// The outcome of received server reply.
+#[derive(PartialEq)]
+#[derive(Debug)]
+pub enum Event {
+  /// The command succeeded (or the greeting, the start of data transfer or unsolicited
+  /// reply is received).
+  Reply(Reply),
+  /// The login succeeded, any command can be sent.
+  LoggedIn,
+  /// The server is ready for data connection on the address (`PASV` or `EPSV` reply).
+  EndpointReady(SocketAddr),
+  /// The server confirmed the end of data transfer.
+  TransferComplete,
+  /// The command is rejected by server (the next command can be sent), or the reply is
+  /// not valid (the client is closed, and the connection should be closed as well).
+  Error(FtpError),
+}
+
+enum Side {
+  Receiving(FtpReceiver),
+  Transmitting(FtpTransmitter),
+}
+
+/// FTP client, which expects the reply for one command at time: the next command
+/// can be sent only after the reply event for the previous one has been polled.
+pub struct FtpClient {
+  side: Option<Side>,
+  password: Option<String>,
+  transferring: bool,
+  buffer: Vec<u8>,
+  events: VecDeque<Event>,
+  transmits: VecDeque<Vec<u8>>,
+}
+
+impl FtpClient {
+
+  /// Creates new client, which expects the server greeting.
+  pub fn new() -> Self {
+    FtpClient {
+      side: Some(Side::Receiving(FtpReceiver::new())),
+      password: None,
+      transferring: false,
+      buffer: vec![0; INITIAL_BUFFER_SIZE],
+      events: VecDeque::new(),
+      transmits: VecDeque::new(),
+    }
+  }
+
+  /// Feeds newly received bytes from the control connection; there might be several
+  /// replies (or incomplete reply) in the `data`. The reply, received when no command
+  /// has been sent (e.g. `421` before the server closes connection), is handled as well,
+  /// the client remains idle.
+  pub fn handle_input(&mut self, data: &[u8]) {
+    let mut data = data;
+    loop {
+      let receiver = match self.side.take() {
+        Some(Side::Receiving(receiver)) => receiver,
+        Some(Side::Transmitting(transmitter)) => {
+          self.side = Some(Side::Transmitting(transmitter));
+          self.on_unsolicited(data);
+          return;
+        },
+        None => return,
+      };
+      match receiver.try_advance(data) {
+        Ok(transmitter) => self.on_reply(transmitter),
+        Err(mut receiver) => match receiver.take_error() {
+          None | Some(FtpError::NotEnoughData) => { self.side = Some(Side::Receiving(receiver)); return; },
+          Some(error @ FtpError::ServerError(_, _)) | Some(error @ FtpError::AuthFailed) => {
+            self.password = None;
+            self.transferring = false;
+            self.side = Some(Side::Transmitting(receiver.to_transmitter()));
+            self.events.push_back(Event::Error(error));
+          },
+          Some(error) => { self.events.push_back(Event::Error(error)); return; },
+        },
+      }
+      // the next reply might have been already received along with the previous one
+      data = &[];
+    }
+  }
+
+  /// Returns the next event, if any.
+  pub fn poll_event(&mut self) -> Option<Event> {
+    self.events.pop_front()
+  }
+
+  /// Returns the next serialized command to be sent via control connection, if any.
+  pub fn poll_transmit(&mut self) -> Option<Vec<u8>> {
+    self.transmits.pop_front()
+  }
+
+  /// Returns `true` if the client does not wait for any reply, i.e. the next command can be sent.
+  pub fn is_idle(&self) -> bool {
+    match self.side {
+      Some(Side::Transmitting(_)) => true,
+      _ => false,
+    }
+  }
+
+  /// Returns `true` if the invalid reply has been received, and the client can't be used further.
+  pub fn is_closed(&self) -> bool {
+    self.side.is_none()
+  }
+
+  /// Returns the underlying transmitter when the client is idle, e.g. to get the results of
+  /// commands (`get_wd`, `get_features` etc.), to parse directory listings or to set encoding.
+  pub fn transmitter(&mut self) -> Option<&mut FtpTransmitter> {
+    match self.side {
+      Some(Side::Transmitting(ref mut transmitter)) => Some(transmitter),
+      _ => None,
+    }
+  }
+
+  /// Sends `login`, and then `password`, if the server asks for it; `Event::LoggedIn`
+  /// is emitted on success.
+  pub fn login(&mut self, login: &str, password: &str) -> Result<(), FtpError> {
+    let result = self.send("login", |transmitter, buffer, count| transmitter.send_login(buffer, count, login));
+    if result.is_ok() {
+      self.password = Some(password.to_string());
+    }
+    result
+  }
+
+  /// Sends `PWD`; the directory is available via `transmitter()` after the reply.
+  pub fn send_pwd_req(&mut self) -> Result<(), FtpError> {
+    self.send("send_pwd_req", |transmitter, buffer, count| transmitter.send_pwd_req(buffer, count))
+  }
+
+  /// Changes remote working directory to `path`.
+  pub fn send_cwd_req(&mut self, path: &str) -> Result<(), FtpError> {
+    self.send("send_cwd_req", |transmitter, buffer, count| transmitter.send_cwd_req(buffer, count, path))
+  }
+
+  /// Changes remote working directory to the parent one.
+  pub fn send_cdup_req(&mut self) -> Result<(), FtpError> {
+    self.send("send_cdup_req", |transmitter, buffer, count| transmitter.send_cdup_req(buffer, count))
+  }
+
+  /// Creates remote directory.
+  pub fn send_mkd_req(&mut self, path: &str) -> Result<(), FtpError> {
+    self.send("send_mkd_req", |transmitter, buffer, count| transmitter.send_mkd_req(buffer, count, path))
+  }
+
+  /// Removes remote directory.
+  pub fn send_rmd_req(&mut self, path: &str) -> Result<(), FtpError> {
+    self.send("send_rmd_req", |transmitter, buffer, count| transmitter.send_rmd_req(buffer, count, path))
+  }
+
+  /// Deletes remote file.
+  pub fn send_dele_req(&mut self, path: &str) -> Result<(), FtpError> {
+    self.send("send_dele_req", |transmitter, buffer, count| transmitter.send_dele_req(buffer, count, path))
+  }
+
+  /// Starts renaming of `path`; `send_rnto_req` should follow the reply.
+  pub fn send_rnfr_req(&mut self, path: &str) -> Result<(), FtpError> {
+    self.send("send_rnfr_req", |transmitter, buffer, count| transmitter.send_rnfr_req(buffer, count, path))
+  }
+
+  /// Completes renaming, started by `send_rnfr_req`.
+  pub fn send_rnto_req(&mut self, path: &str) -> Result<(), FtpError> {
+    self.send("send_rnto_req", |transmitter, buffer, count| transmitter.send_rnto_req(buffer, count, path))
+  }
+
+  /// Sets up data transfer mode (`TYPE`).
+  pub fn send_type_req(&mut self, data_type: DataMode) -> Result<(), FtpError> {
+    self.send("send_type_req", |transmitter, buffer, count| transmitter.send_type_req(buffer, count, data_type.clone()))
+  }
+
+  /// Sends `SYST`; the remote system is available via `transmitter()` after the reply.
+  pub fn send_system_req(&mut self) -> Result<(), FtpError> {
+    self.send("send_system_req", |transmitter, buffer, count| transmitter.send_system_req(buffer, count))
+  }
+
+  /// Sends `FEAT`; the announced features are available via `transmitter()` after the reply.
+  pub fn send_feat_req(&mut self) -> Result<(), FtpError> {
+    self.send("send_feat_req", |transmitter, buffer, count| transmitter.send_feat_req(buffer, count))
+  }
+
+  /// Switches the session into UTF-8 encoding (`OPTS UTF8 ON`).
+  pub fn send_opts_utf8_req(&mut self) -> Result<(), FtpError> {
+    self.send("send_opts_utf8_req", |transmitter, buffer, count| transmitter.send_opts_utf8_req(buffer, count))
+  }
+
+  /// Requests the size of remote file.
+  pub fn send_size_req(&mut self, path: &str) -> Result<(), FtpError> {
+    self.send("send_size_req", |transmitter, buffer, count| transmitter.send_size_req(buffer, count, path))
+  }
+
+  /// Requests the modification time of remote file.
+  pub fn send_mdtm_req(&mut self, path: &str) -> Result<(), FtpError> {
+    self.send("send_mdtm_req", |transmitter, buffer, count| transmitter.send_mdtm_req(buffer, count, path))
+  }
+
+  /// Requests the facts of remote file (or of the current directory).
+  pub fn send_mlst_req(&mut self, path: Option<&str>) -> Result<(), FtpError> {
+    self.send("send_mlst_req", |transmitter, buffer, count| transmitter.send_mlst_req(buffer, count, path))
+  }
+
+  /// Requests passive data connection; `Event::EndpointReady` is emitted on success.
+  pub fn send_pasv_req(&mut self) -> Result<(), FtpError> {
+    self.send("send_pasv_req", |transmitter, buffer, count| transmitter.send_pasv_req(buffer, count))
+  }
+
+  /// Requests extended passive data connection; `Event::EndpointReady` holds the `peer` address.
+  pub fn send_epsv_req(&mut self, peer: IpAddr) -> Result<(), FtpError> {
+    self.send("send_epsv_req", |transmitter, buffer, count| transmitter.send_epsv_req(buffer, count, peer))
+  }
+
+  /// Requests active data connection to IPv4 `addr` and `port` of the client.
+  pub fn send_port_req(&mut self, addr: Ipv4Addr, port: u16) -> Result<(), FtpError> {
+    self.send("send_port_req", |transmitter, buffer, count| transmitter.send_port_req(buffer, count, addr, port))
+  }
+
+  /// Requests active data connection to `addr` of the client (`EPRT`, IPv4 or IPv6).
+  pub fn send_eprt_req(&mut self, addr: SocketAddr) -> Result<(), FtpError> {
+    self.send("send_eprt_req", |transmitter, buffer, count| transmitter.send_eprt_req(buffer, count, addr))
+  }
+
+  /// Sets up restart offset for the following file transfer command.
+  pub fn send_rest_req(&mut self, offset: u64) -> Result<(), FtpError> {
+    self.send("send_rest_req", |transmitter, buffer, count| transmitter.send_rest_req(buffer, count, offset))
+  }
+
+  /// Sends `LIST`; `Event::TransferComplete` is emitted as soon as the server confirms that
+  /// the listing has been sent via data connection.
+  pub fn send_list_req(&mut self, argument: Option<&str>) -> Result<(), FtpError> {
+    self.send("send_list_req", |transmitter, buffer, count| transmitter.send_list_req(buffer, count, argument))
+  }
+
+  /// Sends `NLST`; `Event::TransferComplete` is emitted, when the list of names has been sent.
+  pub fn send_nlst_req(&mut self, argument: Option<&str>) -> Result<(), FtpError> {
+    self.send("send_nlst_req", |transmitter, buffer, count| transmitter.send_nlst_req(buffer, count, argument))
+  }
+
+  /// Sends `MLSD`; `Event::TransferComplete` is emitted, when the listing has been sent.
+  pub fn send_mlsd_req(&mut self, path: Option<&str>) -> Result<(), FtpError> {
+    self.send("send_mlsd_req", |transmitter, buffer, count| transmitter.send_mlsd_req(buffer, count, path))
+  }
+
+  /// Downloads remote file via data connection; `Event::TransferComplete` is emitted at the end.
+  pub fn send_get_req(&mut self, file_path: &str) -> Result<(), FtpError> {
+    self.send("send_get_req", |transmitter, buffer, count| transmitter.send_get_req(buffer, count, file_path))
+  }
+
+  /// Uploads file via data connection; `Event::TransferComplete` is emitted at the end.
+  pub fn send_put_req(&mut self, file_path: &str) -> Result<(), FtpError> {
+    self.send("send_put_req", |transmitter, buffer, count| transmitter.send_put_req(buffer, count, file_path))
+  }
+
+  /// Appends data to remote file via data connection; `Event::TransferComplete` is emitted at the end.
+  pub fn send_append_req(&mut self, file_path: &str) -> Result<(), FtpError> {
+    self.send("send_append_req", |transmitter, buffer, count| transmitter.send_append_req(buffer, count, file_path))
+  }
+
+  /// Serializes the command into transmit queue; the buffer is enlarged, if the command
+  /// does not fit it.
+  fn send<C>(&mut self, attempted: &str, command: C) -> Result<(), FtpError>
+    where C: Fn(FtpTransmitter, &mut [u8], &mut usize) -> Result<FtpReceiver, FtpTransmitter> {
+    let mut transmitter = match self.side.take() {
+      Some(Side::Transmitting(transmitter)) => transmitter,
+      other => {
+        let current = match other {
+          Some(Side::Receiving(ref receiver)) => format!("{}", receiver.internals.state),
+          _ => "closed connection".to_string(),
+        };
+        self.side = other;
+        return Err(FtpError::InvalidState { current: current, attempted: attempted.to_string() });
+      },
+    };
+    loop {
+      let mut count = 0;
+      match command(transmitter, &mut self.buffer, &mut count) {
+        Ok(receiver) => {
+          self.transmits.push_back(self.buffer[0 .. count].to_vec());
+          self.side = Some(Side::Receiving(receiver));
+          return Ok(());
+        },
+        Err(mut rejected) => match rejected.take_error() {
+          Some(FtpError::BufferTooSmall { needed }) => {
+            self.buffer.resize(needed, 0);
+            transmitter = rejected;
+          },
+          error => {
+            self.side = Some(Side::Transmitting(rejected));
+            return Err(error.unwrap_or(FtpError::GarbageData));
+          },
+        },
+      }
+    }
+  }
+
+  /// Emits the events for the replies, received when the client is idle: rejections are
+  /// reported as errors, the other replies as is.
+  fn on_unsolicited(&mut self, data: &[u8]) {
+    let mut data = data;
+    loop {
+      let result = match self.side {
+        Some(Side::Transmitting(ref mut transmitter)) => transmitter.take_unsolicited_reply(data),
+        _ => return,
+      };
+      data = &[];
+      match result {
+        Ok(reply) => {
+          let event = if reply.code >= 400 {
+            Event::Error(FtpError::ServerError(reply.code, reply.lines.join("\n")))
+          } else {
+            Event::Reply(reply)
+          };
+          self.events.push_back(event);
+        },
+        Err(FtpError::NotEnoughData) => return,
+        Err(error) => {
+          self.side = None;
+          self.events.push_back(Event::Error(error));
+          return;
+        },
+      }
+    }
+  }
+
+  /// Emits the event for successful reply, and sends password or awaits the end of data
+  /// transfer, if needed.
+  fn on_reply(&mut self, mut transmitter: FtpTransmitter) {
+    let reply = transmitter.get_last_reply().unwrap().clone();
+    if self.transferring {
+      self.transferring = false;
+      self.events.push_back(Event::TransferComplete);
+      self.side = Some(Side::Transmitting(transmitter));
+      return;
+    }
+    match reply.code {
+      PASSWORD_EXPECTED if self.password.is_some() => {
+        let password = self.password.take().unwrap();
+        self.side = Some(Side::Transmitting(transmitter));
+        if let Err(error) = self.send("send_password", |transmitter, buffer, count| transmitter.send_password(buffer, count, &password)) {
+          self.events.push_back(Event::Error(error));
+        }
+      },
+      LOGGED_IN => {
+        self.password = None;
+        self.events.push_back(Event::LoggedIn);
+        self.side = Some(Side::Transmitting(transmitter));
+      },
+      PASSIVE_MODE | EXTENDED_PASSIVE_MODE => {
+        match transmitter.take_endpoint() {
+          Some(endpoint) => self.events.push_back(Event::EndpointReady(endpoint)),
+          None           => self.events.push_back(Event::Reply(reply)),
+        };
+        self.side = Some(Side::Transmitting(transmitter));
+      },
+      DATA_CONNECTION_OPENED | OPENNING_DATA_CONNECTION => {
+        self.transferring = true;
+        self.events.push_back(Event::Reply(reply));
+        self.side = Some(Side::Receiving(transmitter.to_receiver()));
+      },
+      _ => {
+        self.events.push_back(Event::Reply(reply));
+        self.side = Some(Side::Transmitting(transmitter));
+      },
+    }
+  }
+}
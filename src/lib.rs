@@ -5,19 +5,29 @@ use regex::Regex;
 use std::str;
 use std::fmt;
 use std::rc::Rc;
-use std::net::Ipv4Addr;
-use std::ptr;
+use std::collections::HashMap;
+use std::time::{Duration, SystemTime, UNIX_EPOCH};
+use std::net::{IpAddr, Ipv4Addr, SocketAddr, SocketAddrV4};
 
+pub mod client;
+pub mod session;
+
+const DATA_CONNECTION_OPENED:u32   = 125;
 const OPENNING_DATA_CONNECTION:u32 = 150;
 const OPERATION_SUCCESS:u32        = 200;
+const COMMAND_SUPERFLUOUS:u32      = 202;
+const SYSTEM_STATUS:u32            = 211;
+const FILE_STATUS:u32              = 213;
 const SYSTEM_RECEIVED:u32          = 215;
 const LOGGED_EXPECTED:u32          = 220;
 const CLOSING_DATA_CONNECTION:u32  = 226;
 const PASSIVE_MODE:u32             = 227;
+const EXTENDED_PASSIVE_MODE:u32    = 229;
 const LOGGED_IN:u32                = 230;
-const CWD_CONFIRMED:u32            = 250;
+const FILE_ACTION_OK:u32           = 250;
 const PATHNAME_AVAILABLE:u32       = 257;
 const PASSWORD_EXPECTED:u32        = 331;
+const PENDING_FURTHER_INFO:u32     = 350;
 const AUTHENTICATION_FAILED:u32    = 530;
 
 
@@ -39,6 +49,144 @@ impl fmt::Display for DataMode {
   }
 }
 
+/// Text encoding of the session, i.e. of commands arguments, replies and listings.
+/// The bytes, undefined in `Cp1251`, are mapped to the same code points, so the
+/// decoding never fails (except `Utf8`) and the encoding restores the original bytes.
+#[derive(Clone, Copy)]
+#[derive(PartialEq)]
+#[derive(Debug)]
+pub enum Encoding {
+  Utf8,
+  /// ISO-8859-1
+  Latin1,
+  /// Windows-1251 (Cyrillic)
+  Cp1251,
+}
+
+/// Characters of `0x80 .. 0xC0` bytes of Windows-1251 encoding; the rest ones are ASCII
+/// or continuous range of Cyrillic letters `U+0410 .. U+044F`.
+const CP1251_HIGH: [char; 64] = [
+  '\u{0402}', '\u{0403}', '\u{201A}', '\u{0453}', '\u{201E}', '\u{2026}', '\u{2020}', '\u{2021}',
+  '\u{20AC}', '\u{2030}', '\u{0409}', '\u{2039}', '\u{040A}', '\u{040C}', '\u{040B}', '\u{040F}',
+  '\u{0452}', '\u{2018}', '\u{2019}', '\u{201C}', '\u{201D}', '\u{2022}', '\u{2013}', '\u{2014}',
+  '\u{0098}', '\u{2122}', '\u{0459}', '\u{203A}', '\u{045A}', '\u{045C}', '\u{045B}', '\u{045F}',
+  '\u{00A0}', '\u{040E}', '\u{045E}', '\u{0408}', '\u{00A4}', '\u{0490}', '\u{00A6}', '\u{00A7}',
+  '\u{0401}', '\u{00A9}', '\u{0404}', '\u{00AB}', '\u{00AC}', '\u{00AD}', '\u{00AE}', '\u{0407}',
+  '\u{00B0}', '\u{00B1}', '\u{0406}', '\u{0456}', '\u{0491}', '\u{00B5}', '\u{00B6}', '\u{00B7}',
+  '\u{0451}', '\u{2116}', '\u{0454}', '\u{00BB}', '\u{0458}', '\u{0405}', '\u{0455}', '\u{0457}',
+];
+
+impl Encoding {
+
+  /// Decodes bytes into string; returns `None` on invalid UTF-8 sequence.
+  pub fn decode(&self, bytes: &[u8]) -> Option<String> {
+    match self {
+      &Encoding::Utf8   => str::from_utf8(bytes).ok().map(|text| text.to_string()),
+      &Encoding::Latin1 => Some(bytes.iter().map(|&b| b as char).collect()),
+      &Encoding::Cp1251 => Some(bytes.iter().map(|&b| match b {
+        0x00 ..= 0x7F => b as char,
+        0x80 ..= 0xBF => CP1251_HIGH[b as usize - 0x80],
+        _             => ::std::char::from_u32(0x0410 + b as u32 - 0xC0).unwrap(),
+      }).collect()),
+    }
+  }
+
+  /// Encodes string into bytes; returns `None` if some character can't be represented.
+  pub fn encode(&self, text: &str) -> Option<Vec<u8>> {
+    match self {
+      &Encoding::Utf8   => Some(text.as_bytes().to_vec()),
+      &Encoding::Latin1 => text.chars()
+        .map(|ch| if (ch as u32) < 0x100 { Some(ch as u8) } else { None })
+        .collect(),
+      &Encoding::Cp1251 => text.chars()
+        .map(|ch| match ch as u32 {
+          code @ 0x00 ..= 0x7F     => Some(code as u8),
+          code @ 0x0410 ..= 0x044F => Some((code - 0x0410 + 0xC0) as u8),
+          _ => CP1251_HIGH.iter().position(|&high| high == ch).map(|index| (index + 0x80) as u8),
+        })
+        .collect(),
+    }
+  }
+
+  /// Returns the count of bytes, `text` has been decoded from.
+  fn encoded_len(&self, text: &str) -> usize {
+    match self {
+      &Encoding::Utf8 => text.len(),
+      _               => text.chars().count(),
+    }
+  }
+
+  /// Decodes bytes into string, which never fails: each byte, that can't be decoded, is kept
+  /// as `ESCAPED_BYTE + byte` character, so `escaped_bytes` returns exactly the original bytes
+  /// of any part of the string.
+  fn decode_escaped(&self, bytes: &[u8]) -> String {
+    match self {
+      &Encoding::Utf8 => {
+        let mut text = String::new();
+        let mut rest = bytes;
+        while !rest.is_empty() {
+          let (valid, invalid) = match str::from_utf8(rest) {
+            Ok(valid) => (valid, 0),
+            Err(e)    => {
+              let valid = str::from_utf8(&rest[0 .. e.valid_up_to()]).unwrap();
+              (valid, e.error_len().unwrap_or(rest.len() - valid.len()))
+            },
+          };
+          for ch in valid.chars() {
+            if ch as u32 >= ESCAPED_BYTE {
+              // the character collides with escaped bytes, so it is escaped too
+              let mut buffer = [0; 4];
+              text.extend(ch.encode_utf8(&mut buffer).bytes().map(Encoding::escape));
+            } else {
+              text.push(ch);
+            }
+          }
+          text.extend(rest[valid.len() .. valid.len() + invalid].iter().map(|&b| Encoding::escape(b)));
+          rest = &rest[valid.len() + invalid ..];
+        }
+        text
+      },
+      _ => self.decode(bytes).unwrap(),
+    }
+  }
+
+  fn escape(byte: u8) -> char {
+    ::std::char::from_u32(ESCAPED_BYTE + byte as u32).unwrap()
+  }
+
+  /// Returns the original bytes of the string, decoded by `decode_escaped`.
+  fn escaped_bytes(&self, text: &str) -> Vec<u8> {
+    match self {
+      &Encoding::Utf8 => {
+        let mut bytes = Vec::new();
+        for ch in text.chars() {
+          if ch as u32 >= ESCAPED_BYTE {
+            bytes.push((ch as u32 - ESCAPED_BYTE) as u8);
+          } else {
+            let mut buffer = [0; 4];
+            bytes.extend_from_slice(ch.encode_utf8(&mut buffer).as_bytes());
+          }
+        }
+        bytes
+      },
+      _ => self.encode(text).unwrap_or_default(),
+    }
+  }
+
+  /// Converts the string, decoded by `decode_escaped`, into displayable one, i.e. the bytes,
+  /// which can't be decoded, are replaced with `U+FFFD`.
+  fn unescaped(&self, text: &str) -> String {
+    match self {
+      &Encoding::Utf8 => String::from_utf8_lossy(&self.escaped_bytes(text)).into_owned(),
+      _               => text.to_string(),
+    }
+  }
+}
+
+/// The first of 256 characters (the last ones of Unicode private use plane), which keep
+/// undecodable bytes of UTF-8 listings.
+const ESCAPED_BYTE: u32 = 0x10FF00;
+
 enum State {
   NonAuthorized,
   Authorized,
@@ -53,6 +201,20 @@ enum State {
 
   CwdReqSent(String),
   CwdConfirmed,
+  CdupReqSent,
+  CdupConfirmed,
+
+  MkdReqSent(String),
+  DirectoryCreated(String),
+  RmdReqSent(String),
+  DirectoryRemoved,
+
+  DeleReqSent(String),
+  FileDeleted,
+  RenameFromReqSent(String),
+  RenameFromConfirmed(String),
+  RenameToReqSent(String),
+  FileRenamed,
 
   DataTypeReqSent(DataMode),
   DataTypeConfirmed(DataMode),
@@ -60,11 +222,36 @@ enum State {
   SystemReqSent,
   SystemRecived(String, String),
 
+  FeatReqSent,
+  FeaturesReceived(Features),
+
+  Utf8ReqSent,
+  Utf8Enabled,
+
+  SizeReqSent(String),
+  SizeReceived(u64),
+  MdtmReqSent(String),
+  ModificationTimeReceived(Timestamp),
+
   PassiveReqSent,
-  PassiveConfirmed(Ipv4Addr, u16),
+  ExtendedPassiveReqSent(IpAddr),
+  PassiveConfirmed(SocketAddr),
+
+  ActiveReqSent,
+  ActiveConfirmed,
+
+  RestReqSent(u64),
+  RestConfirmed(u64),
+
+  MlstReqSent(String),
+  FileFactsReceived(RemoteFile),
 
   ListReqSent,
+  NlstReqSent,
+  MlsdReqSent,
   FileReqSent,
+  FilePutReqSent,
+  FileAppendReqSent,
 
   DataTransferStarted,
   DataTransferCompleted,
@@ -75,10 +262,27 @@ impl fmt::Display for State {
     match self {
       &State::PathReceived(ref value)              => write!(f, "[state: path-received({})]", value),
       &State::CwdReqSent(ref value)                => write!(f, "[state: cwd-req-sent({})]", value),
+      &State::MkdReqSent(ref value)                => write!(f, "[state: mkd-req-sent({})]", value),
+      &State::DirectoryCreated(ref value)          => write!(f, "[state: directory-created({})]", value),
+      &State::RmdReqSent(ref value)                => write!(f, "[state: rmd-req-sent({})]", value),
+      &State::DeleReqSent(ref value)               => write!(f, "[state: dele-req-sent({})]", value),
+      &State::RenameFromReqSent(ref value)         => write!(f, "[state: rename-from-req-sent({})]", value),
+      &State::RenameFromConfirmed(ref value)       => write!(f, "[state: rename-from-confirmed({})]", value),
+      &State::RenameToReqSent(ref value)           => write!(f, "[state: rename-to-req-sent({})]", value),
       &State::DataTypeReqSent(ref value)           => write!(f, "[state: data-type-req-sent({})]", value),
       &State::DataTypeConfirmed(ref value)         => write!(f, "[state: data-type-confirmed({})]", value),
       &State::SystemRecived(ref name, ref subtype) => write!(f, "[state: system-recieved({}/{})]", name, subtype),
-      &State::PassiveConfirmed(ref addr, ref port) => write!(f, "[state: passive-mode ({}:{})]", addr, port),
+      &State::FeaturesReceived(_)                  => write!(f, "[state: features-received]"),
+      &State::SizeReqSent(ref path)                => write!(f, "[state: size-req-sent({})]", path),
+      &State::SizeReceived(ref size)               => write!(f, "[state: size-received({})]", size),
+      &State::MdtmReqSent(ref path)                => write!(f, "[state: mdtm-req-sent({})]", path),
+      &State::ModificationTimeReceived(ref time)   => write!(f, "[state: modification-time-received({})]", time),
+      &State::MlstReqSent(ref path)                => write!(f, "[state: mlst-req-sent({})]", path),
+      &State::FileFactsReceived(ref file)          => write!(f, "[state: file-facts-received({})]", file.name),
+      &State::ExtendedPassiveReqSent(ref peer)     => write!(f, "[state: extended-passive-req-sent({})]", peer),
+      &State::PassiveConfirmed(ref addr)           => write!(f, "[state: passive-mode ({})]", addr),
+      &State::RestReqSent(ref offset)              => write!(f, "[state: rest-req-sent({})]", offset),
+      &State::RestConfirmed(ref offset)            => write!(f, "[state: rest-confirmed({})]", offset),
       _ => {
         let state = match self {
           &State::NonAuthorized         => "non-authorized",
@@ -89,12 +293,26 @@ impl fmt::Display for State {
           &State::PasswordReqSent       => "password-req-sent",
           &State::PwdReqSent            => "pwd-req-sent",
           &State::SystemReqSent         => "system-req-sent",
+          &State::FeatReqSent           => "feat-req-sent",
+          &State::Utf8ReqSent           => "utf8-req-sent",
+          &State::Utf8Enabled           => "utf8-enabled",
           &State::PassiveReqSent        => "passive-req-sent",
+          &State::ActiveReqSent         => "active-req-sent",
+          &State::ActiveConfirmed       => "active-confirmed",
           &State::ListReqSent           => "list-req-sent",
+          &State::NlstReqSent           => "nlst-req-sent",
+          &State::MlsdReqSent           => "mlsd-req-sent",
           &State::FileReqSent           => "file-req-sent",
+          &State::FilePutReqSent        => "file-put-req-sent",
+          &State::FileAppendReqSent     => "file-append-req-sent",
           &State::DataTransferStarted   => "data-transfer-started",
           &State::DataTransferCompleted => "data-transfer-completed",
           &State::CwdConfirmed          => "cwd-confirmed",
+          &State::CdupReqSent           => "cdup-req-sent",
+          &State::CdupConfirmed         => "cdup-confirmed",
+          &State::DirectoryRemoved      => "directory-removed",
+          &State::FileDeleted           => "file-deleted",
+          &State::FileRenamed           => "file-renamed",
           _ => unreachable!(),
         };
         write!(f, "[state: {}]", state)
@@ -103,21 +321,830 @@ impl fmt::Display for State {
   }
 }
 
-/// Defines files type for parsed `LIST` command.
+/// Date and time in UTC, e.g. the modification time of remote file.
+#[derive(Clone, Copy)]
+#[derive(PartialEq, Eq, PartialOrd, Ord)]
+#[derive(Debug)]
+pub struct Timestamp {
+  pub year: u16,
+  pub month: u8,
+  pub day: u8,
+  pub hour: u8,
+  pub minute: u8,
+  pub second: u8,
+  pub millis: u16,
+}
+
+impl Timestamp {
+
+  /// Parses `YYYYMMDDHHMMSS[.sss]` time-val format (RFC 3659), used by `MDTM` and `MLSD`.
+  pub fn parse_time_val(value: &str) -> Option<Timestamp> {
+    lazy_static! {
+      static ref RE_TIME_VAL: Regex = Regex::new("^([0-9]{4})([0-9]{2})([0-9]{2})([0-9]{2})([0-9]{2})([0-9]{2})(?:\\.([0-9]+))?$").unwrap();
+    }
+    RE_TIME_VAL.captures(value).and_then(|captures|{
+      let millis = captures.at(7).map_or(0, |fraction|{
+        fraction.chars().chain("00".chars()).take(3).collect::<String>().parse().unwrap()
+      });
+      Timestamp {
+        year: captures.at(1).unwrap().parse().unwrap(),
+        month: captures.at(2).unwrap().parse().unwrap(),
+        day: captures.at(3).unwrap().parse().unwrap(),
+        hour: captures.at(4).unwrap().parse().unwrap(),
+        minute: captures.at(5).unwrap().parse().unwrap(),
+        second: captures.at(6).unwrap().parse().unwrap(),
+        millis: millis,
+      }.validated()
+    })
+  }
+
+  fn validated(self) -> Option<Timestamp> {
+    let valid = self.month >= 1 && self.month <= 12 && self.day >= 1 && self.day <= self.days_in_month()
+      && self.hour < 24 && self.minute < 60 && self.second <= 60;
+    if valid { Some(self) } else { None }
+  }
+
+  /// Count of days in the month of the timestamp (the month should be valid).
+  fn days_in_month(&self) -> u8 {
+    match self.month {
+      2 => {
+        let leap = self.year % 4 == 0 && (self.year % 100 != 0 || self.year % 400 == 0);
+        if leap { 29 } else { 28 }
+      },
+      4 | 6 | 9 | 11 => 30,
+      _ => 31,
+    }
+  }
+
+  /// Count of days since 1970-01-01 (proleptic Gregorian calendar).
+  fn days_since_epoch(&self) -> i64 {
+    let (month, day) = (self.month as i64, self.day as i64);
+    let year = if month <= 2 { self.year as i64 - 1 } else { self.year as i64 };
+    let era = (if year >= 0 { year } else { year - 399 }) / 400;
+    let year_of_era = year - era * 400;
+    let day_of_year = (153 * ((month + 9) % 12) + 2) / 5 + day - 1;
+    let day_of_era = year_of_era * 365 + year_of_era / 4 - year_of_era / 100 + day_of_year;
+    era * 146097 + day_of_era - 719468
+  }
+
+  /// Converts seconds since 1970-01-01 into timestamp, or returns `None` if the year
+  /// is out of range.
+  fn from_unix_seconds(seconds: i64) -> Option<Timestamp> {
+    let days = match if seconds >= 0 { Some(seconds) } else { seconds.checked_sub(86399) } {
+      Some(value) => value / 86400,
+      None        => return None,
+    };
+    let second_of_day = seconds - days * 86400;
+    let shifted = days + 719468;
+    let era = (if shifted >= 0 { shifted } else { shifted - 146096 }) / 146097;
+    let day_of_era = shifted - era * 146097;
+    let year_of_era = (day_of_era - day_of_era / 1460 + day_of_era / 36524 - day_of_era / 146096) / 365;
+    let day_of_year = day_of_era - (365 * year_of_era + year_of_era / 4 - year_of_era / 100);
+    let month_index = (5 * day_of_year + 2) / 153;
+    let month = if month_index < 10 { month_index + 3 } else { month_index - 9 };
+    let year = year_of_era + era * 400 + if month <= 2 { 1 } else { 0 };
+    if year < 0 || year > u16::max_value() as i64 {
+      return None;
+    }
+    Some(Timestamp {
+      year: year as u16,
+      month: month as u8,
+      day: (day_of_year - (153 * month_index + 2) / 5 + 1) as u8,
+      hour: (second_of_day / 3600) as u8,
+      minute: (second_of_day % 3600 / 60) as u8,
+      second: (second_of_day % 60) as u8,
+      millis: 0,
+    })
+  }
+
+  /// Converts `SystemTime` into timestamp, e.g. to get the current time.
+  /// Panics if the year does not fit into `u16`.
+  pub fn from_system_time(time: SystemTime) -> Timestamp {
+    let (seconds, millis) = match time.duration_since(UNIX_EPOCH) {
+      Ok(since) => (since.as_secs() as i64, since.subsec_nanos() / 1_000_000),
+      Err(err)  => {
+        let before = err.duration();
+        let nanos = before.subsec_nanos();
+        if nanos == 0 {
+          (-(before.as_secs() as i64), 0)
+        } else {
+          (-(before.as_secs() as i64) - 1, (1_000_000_000 - nanos) / 1_000_000)
+        }
+      },
+    };
+    let timestamp = Timestamp::from_unix_seconds(seconds).expect("the year is out of range");
+    Timestamp { millis: millis as u16, ..timestamp }
+  }
+
+  /// Parses the date column of `LIST` output: Unix (`Jul 19  2014` or `Nov 02 15:30`),
+  /// DOS (`10-18-26  03:14PM`), VMS (`29-JAN-1996 03:33:12`) or MVS (`2026/10/18`) one.
+  /// Unix dates without year are resolved to the latest date not after `now`
+  /// (a day ahead is tolerated due to time zones).
+  pub fn parse_list_date(date: &str, now: &Timestamp) -> Option<Timestamp> {
+    lazy_static! {
+      static ref RE_UNIX_DATE: Regex = Regex::new("^([A-Za-z]{3}) +([0-9]{1,2}) +(?:([0-9]{4})|([0-9]{1,2}):([0-9]{2}))$").unwrap();
+      static ref RE_DOS_DATE: Regex = Regex::new("^([0-9]{2})-([0-9]{2})-([0-9]{2}|[0-9]{4}) +([0-9]{1,2}):([0-9]{2})([AaPp][Mm])?$").unwrap();
+      static ref RE_VMS_DATE: Regex = Regex::new("^([0-9]{1,2})-([A-Za-z]{3})-([0-9]{4}) +([0-9]{1,2}):([0-9]{2})(?::([0-9]{2})(?:\\.[0-9]+)?)?$").unwrap();
+      static ref RE_MVS_DATE: Regex = Regex::new("^([0-9]{4})/([0-9]{2})/([0-9]{2})$").unwrap();
+    }
+    const MONTHS: [&'static str; 12] = ["JAN", "FEB", "MAR", "APR", "MAY", "JUN", "JUL", "AUG", "SEP", "OCT", "NOV", "DEC"];
+    let month_of = |name: &str| MONTHS.iter().position(|month| *month == name.to_uppercase()).map(|index| index as u8 + 1);
+    // missing (optional) values are zeros
+    let number = |value: Option<&str>| value.map_or(Some(0), |value| value.parse::<u16>().ok());
+    let midnight = Timestamp { year: 0, month: 1, day: 1, hour: 0, minute: 0, second: 0, millis: 0 };
+
+    if let Some(captures) = RE_UNIX_DATE.captures(date) {
+      let time = match (month_of(captures.at(1).unwrap()), number(captures.at(2)), number(captures.at(4)), number(captures.at(5))) {
+        (Some(month), Some(day), Some(hour), Some(minute)) => Timestamp {
+          month: month,
+          day: day as u8,
+          hour: hour as u8,
+          minute: minute as u8,
+          ..midnight
+        },
+        _ => return None,
+      };
+      return match captures.at(3) {
+        Some(year) => number(Some(year)).and_then(|year| Timestamp { year: year, ..time }.validated()),
+        None => Timestamp { year: now.year, ..time }.validated().and_then(|this_year|{
+          if this_year.days_since_epoch() > now.days_since_epoch() + 1 {
+            now.year.checked_sub(1).and_then(|year| Timestamp { year: year, ..this_year }.validated())
+          } else {
+            Some(this_year)
+          }
+        }),
+      };
+    }
+    if let Some(captures) = RE_DOS_DATE.captures(date) {
+      let (month, day, year, hour, minute) = match (number(captures.at(1)), number(captures.at(2)), number(captures.at(3)),
+                                                    number(captures.at(4)), number(captures.at(5))) {
+        (Some(month), Some(day), Some(year), Some(hour), Some(minute)) => (month, day, year, hour, minute),
+        _ => return None,
+      };
+      let year = match year {
+        year if year >= 100 => year,
+        year if year >= 70  => 1900 + year,
+        year                => 2000 + year,
+      };
+      let hour = match captures.at(6).map(|suffix| suffix.to_uppercase()) {
+        Some(_) if hour == 0 || hour > 12  => return None,
+        Some(ref suffix) if suffix == "AM" => hour % 12,
+        Some(_)                            => hour % 12 + 12,
+        None                               => hour,
+      };
+      return Timestamp {
+        year: year,
+        month: month as u8,
+        day: day as u8,
+        hour: hour as u8,
+        minute: minute as u8,
+        ..midnight
+      }.validated();
+    }
+    if let Some(captures) = RE_VMS_DATE.captures(date) {
+      return match (month_of(captures.at(2).unwrap()), number(captures.at(3)), number(captures.at(1)),
+                    number(captures.at(4)), number(captures.at(5)), number(captures.at(6))) {
+        (Some(month), Some(year), Some(day), Some(hour), Some(minute), Some(second)) => Timestamp {
+          year: year,
+          month: month,
+          day: day as u8,
+          hour: hour as u8,
+          minute: minute as u8,
+          second: second as u8,
+          ..midnight
+        }.validated(),
+        _ => None,
+      };
+    }
+    RE_MVS_DATE.captures(date).and_then(|captures|{
+      match (number(captures.at(1)), number(captures.at(2)), number(captures.at(3))) {
+        (Some(year), Some(month), Some(day)) => Timestamp {
+          year: year,
+          month: month as u8,
+          day: day as u8,
+          ..midnight
+        }.validated(),
+        _ => None,
+      }
+    })
+  }
+
+  /// Converts into `SystemTime`.
+  pub fn to_system_time(&self) -> SystemTime {
+    let seconds = self.days_since_epoch() * 86400
+      + self.hour as i64 * 3600 + self.minute as i64 * 60 + self.second as i64;
+    let millis = Duration::from_millis(self.millis as u64);
+    if seconds >= 0 {
+      UNIX_EPOCH + Duration::from_secs(seconds as u64) + millis
+    } else {
+      UNIX_EPOCH - Duration::from_secs((-seconds) as u64) + millis
+    }
+  }
+}
+
+impl fmt::Display for Timestamp {
+  fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+    write!(f, "{:04}-{:02}-{:02}T{:02}:{:02}:{:02}.{:03}Z",
+           self.year, self.month, self.day, self.hour, self.minute, self.second, self.millis)
+  }
+}
+
+/// Server capabilities (extensions), announced in `FEAT` reply (RFC 2389).
+#[derive(Clone, Default)]
+#[derive(PartialEq)]
+#[derive(Debug)]
+pub struct Features {
+  pub mdtm: bool,
+  pub size: bool,
+  pub rest_stream: bool,
+  pub tvfs: bool,
+  pub utf8: bool,
+  pub epsv: bool,
+  pub eprt: bool,
+  /// Facts, supported by `MLST` and `MLSD` commands (the enabled ones are marked with `*`),
+  /// or `None` if the commands are not supported.
+  pub mlst: Option<Vec<String>>,
+  /// Other features: the upper-cased name and the parameters.
+  pub other: HashMap<String, String>,
+}
+
+impl Features {
+
+  /// Parses `FEAT` reply lines, i.e. one feature per line prefixed by space.
+  fn parse(reply: &Reply) -> Features {
+    let mut features = Features::default();
+    // the first line is "Features:", and the last one is "End"
+    let count = reply.lines.len();
+    let feature_lines = if count > 2 { &reply.lines[1 .. count - 1] } else { &reply.lines[0 .. 0] };
+    for line in feature_lines {
+      let line = line.trim();
+      let (name, params) = match line.find(' ') {
+        Some(pos) => (line[0 .. pos].to_uppercase(), line[pos + 1 ..].trim()),
+        None      => (line.to_uppercase(), ""),
+      };
+      match name.as_str() {
+        ""     => {},
+        "MDTM" => { features.mdtm = true; },
+        "SIZE" => { features.size = true; },
+        "TVFS" => { features.tvfs = true; },
+        "UTF8" => { features.utf8 = true; },
+        "EPSV" => { features.epsv = true; },
+        "EPRT" => { features.eprt = true; },
+        "REST" if params.eq_ignore_ascii_case("STREAM") => { features.rest_stream = true; },
+        "MLST" => {
+          let facts = params.split(';').filter(|fact| !fact.is_empty()).map(|fact| fact.to_string()).collect();
+          features.mlst = Some(facts);
+        },
+        _ => { features.other.insert(name.clone(), params.to_string()); },
+      }
+    }
+    features
+  }
+}
+
+/// Read, write and execute access of the file owner, group or others.
+#[derive(Clone, Copy, Default)]
+#[derive(PartialEq, Eq)]
+#[derive(Debug)]
+pub struct Access {
+  pub read: bool,
+  pub write: bool,
+  pub execute: bool,
+}
+
+impl Access {
+
+  fn from_bits(bits: u32) -> Access {
+    Access { read: bits & 4 != 0, write: bits & 2 != 0, execute: bits & 1 != 0 }
+  }
+
+  fn bits(&self) -> u32 {
+    (if self.read { 4 } else { 0 }) | (if self.write { 2 } else { 0 }) | (if self.execute { 1 } else { 0 })
+  }
+}
+
+/// Unix file permissions of `RemoteFile`.
+#[derive(Clone, Copy, Default)]
+#[derive(PartialEq, Eq)]
+#[derive(Debug)]
+pub struct Permissions {
+  pub user: Access,
+  pub group: Access,
+  pub other: Access,
+  pub setuid: bool,
+  pub setgid: bool,
+  pub sticky: bool,
+}
+
+impl Permissions {
+
+  /// Parses symbolic permissions, e.g. `rwsr-xr-t` (the mode column of `LIST` output
+  /// without the file type).
+  pub fn parse(value: &str) -> Option<Permissions> {
+    let chars: Vec<char> = value.chars().collect();
+    if chars.len() != 9 {
+      return None;
+    }
+    let mut accesses = [Access::default(); 3];
+    let mut specials = [false; 3];
+    for index in 0 .. 3 {
+      let triple = &chars[index * 3 .. index * 3 + 3];
+      accesses[index].read = match triple[0] { 'r' => true, '-' => false, _ => return None };
+      accesses[index].write = match triple[1] { 'w' => true, '-' => false, _ => return None };
+      let (execute, special) = match triple[2] {
+        '-' => (false, false),
+        'x' => (true, false),
+        's' if index < 2 => (true, true),
+        'S' if index < 2 => (false, true),
+        // mandatory locking
+        'l' | 'L' if index == 1 => (false, true),
+        't' if index == 2 => (true, true),
+        'T' if index == 2 => (false, true),
+        _ => return None,
+      };
+      accesses[index].execute = execute;
+      specials[index] = special;
+    }
+    Some(Permissions {
+      user: accesses[0],
+      group: accesses[1],
+      other: accesses[2],
+      setuid: specials[0],
+      setgid: specials[1],
+      sticky: specials[2],
+    })
+  }
+
+  /// Converts VMS protection, e.g. `(RWED,RWED,RE,)`: the owner, group and world
+  /// categories are mapped to user, group and other ones (delete access is skipped).
+  fn parse_vms(value: &str) -> Option<Permissions> {
+    let categories: Vec<&str> = value.trim_matches(|c| c == '(' || c == ')').split(',').collect();
+    if categories.len() != 4 {
+      return None;
+    }
+    let access = |category: &str| Access {
+      read: category.contains('R'),
+      write: category.contains('W'),
+      execute: category.contains('E'),
+    };
+    Some(Permissions {
+      user: access(categories[1]),
+      group: access(categories[2]),
+      other: access(categories[3]),
+      ..Permissions::default()
+    })
+  }
+
+  /// Converts numeric mode, e.g. `0o4755`.
+  pub fn from_mode(mode: u32) -> Permissions {
+    Permissions {
+      user: Access::from_bits(mode >> 6),
+      group: Access::from_bits(mode >> 3),
+      other: Access::from_bits(mode),
+      setuid: mode & 0o4000 != 0,
+      setgid: mode & 0o2000 != 0,
+      sticky: mode & 0o1000 != 0,
+    }
+  }
+
+  /// Returns numeric mode, e.g. `0o644`.
+  pub fn mode(&self) -> u32 {
+    (if self.setuid { 0o4000 } else { 0 }) | (if self.setgid { 0o2000 } else { 0 }) | (if self.sticky { 0o1000 } else { 0 })
+      | self.user.bits() << 6 | self.group.bits() << 3 | self.other.bits()
+  }
+}
+
+impl fmt::Display for Permissions {
+  fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+    let triple = |access: &Access, special: bool, special_char: char| {
+      let execute = match (access.execute, special) {
+        (false, false) => '-',
+        (true, false)  => 'x',
+        (true, true)   => special_char,
+        (false, true)  => special_char.to_ascii_uppercase(),
+      };
+      format!("{}{}{}", if access.read { 'r' } else { '-' }, if access.write { 'w' } else { '-' }, execute)
+    };
+    write!(f, "{}{}{}", triple(&self.user, self.setuid, 's'), triple(&self.group, self.setgid, 's'),
+           triple(&self.other, self.sticky, 't'))
+  }
+}
+
+/// Defines files type for parsed `LIST`, `MLSD` or `MLST` commands.
+#[derive(Clone)]
 #[derive(PartialEq)]
 #[derive(Debug)]
 pub enum RemoteFileKind {
   File,
   Directory,
+  /// The listed directory itself (`type=cdir` fact).
+  CurrentDirectory,
+  /// The parent of the listed directory (`type=pdir` fact).
+  ParentDirectory,
+  Symlink { target: String },
+  CharDevice,
+  BlockDevice,
+  Pipe,
+  Socket,
+  /// Other (e.g. OS-specific) type, as it was reported.
+  Other(String),
+}
+
+impl Default for RemoteFileKind {
+  fn default() -> Self { RemoteFileKind::File }
+}
+
+/// Represents single name of `NLST` listing.
+#[derive(Clone)]
+#[derive(PartialEq)]
+#[derive(Debug)]
+pub struct RemoteName {
+  /// The bytes, which can't be decoded, are replaced with `U+FFFD`.
+  pub name: String,
+  /// The name as it was received, i.e. in the session encoding.
+  pub raw_name: Vec<u8>,
 }
 
-/// Represents single item parsed `LIST` command.
+/// Represents single item parsed `LIST`, `MLSD` or `MLST` command.
+/// Fields, which are not reported by the server, are left `None` (or empty).
+#[derive(Clone, Default)]
 #[derive(PartialEq)]
 #[derive(Debug)]
 pub struct RemoteFile {
   pub kind: RemoteFileKind,
-  pub size: usize,
+  pub size: u64,
   pub name: String,
+  /// The name as it was received, i.e. in the session encoding; the bytes of `name`, which
+  /// can't be decoded, are replaced with `U+FFFD`.
+  pub raw_name: Vec<u8>,
+  pub modified: Option<Timestamp>,
+  /// Server-wide unique identifier of the file (`unique` fact).
+  pub unique: Option<String>,
+  /// Allowed operations on the file (`perm` fact), e.g. `"adfr"`.
+  pub perm: Option<String>,
+  pub unix_mode: Option<u32>,
+  /// Parsed from the mode column of `LIST` output (e.g. `-rw-r--r--`) or from the mode facts.
+  pub permissions: Option<Permissions>,
+  pub links: Option<u64>,
+  pub owner: Option<String>,
+  pub group: Option<String>,
+  /// The date column of `LIST` output as is, e.g. `Jul 19  2014`.
+  pub date: Option<String>,
+  /// Unrecognized facts: the lower-cased name and the value.
+  pub facts: HashMap<String, String>,
+}
+
+impl RemoteFile {
+
+  /// Sets up the raw name from the line, decoded by `Encoding::decode_escaped`, and makes
+  /// all the text fields displayable.
+  fn unescaped(self, encoding: &Encoding) -> RemoteFile {
+    let unescape = |text: String| encoding.unescaped(&text);
+    RemoteFile {
+      kind: match self.kind {
+        RemoteFileKind::Symlink { target } => RemoteFileKind::Symlink { target: unescape(target) },
+        RemoteFileKind::Other(kind) => RemoteFileKind::Other(unescape(kind)),
+        kind => kind,
+      },
+      raw_name: encoding.escaped_bytes(&self.name),
+      name: unescape(self.name),
+      unique: self.unique.map(&unescape),
+      perm: self.perm.map(&unescape),
+      owner: self.owner.map(&unescape),
+      group: self.group.map(&unescape),
+      date: self.date.map(&unescape),
+      facts: self.facts.into_iter().map(|(name, value)| (unescape(name), unescape(value))).collect(),
+      ..self
+    }
+  }
+
+  /// Parses single `MLSD` line or `MLST` reply entry (without the leading space), i.e.
+  /// `fact=value;` pairs, followed by space and the file name (RFC 3659).
+  fn parse_facts(line: &str) -> Result<RemoteFile, FtpError> {
+    let mut file = RemoteFile::default();
+    let mut rest = line;
+    while !rest.starts_with(' ') {
+      let fact = match rest.find(';') {
+        Some(pos) => { let fact = &rest[0 .. pos]; rest = &rest[pos + 1 ..]; fact },
+        None      => return Err(FtpError::GarbageData),
+      };
+      let (name, value) = match fact.find('=') {
+        Some(pos) => (fact[0 .. pos].to_lowercase(), &fact[pos + 1 ..]),
+        None      => return Err(FtpError::GarbageData),
+      };
+      match name.as_str() {
+        "type" => {
+          file.kind = match value.to_lowercase().as_str() {
+            "file" => RemoteFileKind::File,
+            "dir"  => RemoteFileKind::Directory,
+            "cdir" => RemoteFileKind::CurrentDirectory,
+            "pdir" => RemoteFileKind::ParentDirectory,
+            _      => RemoteFileKind::Other(value.to_string()),
+          };
+        },
+        "size" => {
+          file.size = match value.parse() {
+            Ok(size) => size,
+            Err(_)   => return Err(FtpError::GarbageData),
+          };
+        },
+        "modify" => {
+          file.modified = match Timestamp::parse_time_val(value) {
+            Some(time) => Some(time),
+            None       => return Err(FtpError::GarbageData),
+          };
+        },
+        "unix.mode" => {
+          file.unix_mode = match u32::from_str_radix(value, 8) {
+            Ok(mode) => Some(mode),
+            Err(_)   => return Err(FtpError::GarbageData),
+          };
+          file.permissions = file.unix_mode.map(Permissions::from_mode);
+        },
+        "unique" => { file.unique = Some(value.to_string()); },
+        "perm"   => { file.perm = Some(value.to_string()); },
+        _ => { file.facts.insert(name, value.to_string()); },
+      }
+    }
+    let name = &rest[1 ..];
+    if name.is_empty() {
+      return Err(FtpError::GarbageData);
+    }
+    file.name = name.to_string();
+    Ok(file)
+  }
+}
+
+/// Parser of single line of `LIST` output in some format. The built-in parsers
+/// are tried by `FtpTransmitter::parse_list` after the custom ones, added via
+/// `FtpTransmitter::add_list_parser`.
+pub trait ListParser {
+  /// Returns `None`, if the line is not in the format of the parser.
+  fn parse_line(&self, line: &str) -> Option<RemoteFile>;
+
+  /// Checks whether the line is (ignored) header or footer of the listing, e.g. `total 24`.
+  fn is_header(&self, _line: &str) -> bool { false }
+}
+
+/// Parser of Unix `ls -l` style listing, e.g.
+/// `lrwxrwxrwx   1 www-data www-data   11 Nov 02 15:30 my link -> my file.txt`.
+/// The group column is optional, device files have major and minor numbers instead of size.
+pub struct UnixListParser;
+
+impl ListParser for UnixListParser {
+
+  fn parse_line(&self, line: &str) -> Option<RemoteFile> {
+    lazy_static! {
+      static ref RE_MODE: Regex = Regex::new("^([-dlcbpsDn])(.{9})[+@.]?$").unwrap();
+      static ref RE_DAY: Regex = Regex::new("^[0-9]{1,2}$").unwrap();
+      static ref RE_YEAR_OR_TIME: Regex = Regex::new("^(?:[0-9]{4}|[0-9]{1,2}:[0-9]{2})$").unwrap();
+    }
+    const MONTHS: [&'static str; 12] = ["Jan", "Feb", "Mar", "Apr", "May", "Jun", "Jul", "Aug", "Sep", "Oct", "Nov", "Dec"];
+
+    // fields along with their offsets in the line, as the name might contain spaces
+    let mut fields: Vec<(usize, &str)> = Vec::new();
+    let mut start = None;
+    for (pos, ch) in line.char_indices() {
+      match (ch.is_whitespace(), start) {
+        (true, Some(from)) => { fields.push((from, &line[from .. pos])); start = None; },
+        (false, None)      => { start = Some(pos); },
+        _ => {},
+      }
+    }
+    if let Some(from) = start {
+      fields.push((from, &line[from ..]));
+    }
+    if fields.len() < 8 {
+      return None;
+    }
+
+    let (kind, permissions) = match RE_MODE.captures(fields[0].1) {
+      None => return None,
+      Some(captures) => (match captures.at(1).unwrap() {
+        "-" => RemoteFileKind::File,
+        "d" => RemoteFileKind::Directory,
+        "l" => RemoteFileKind::Symlink { target: String::new() },
+        "c" => RemoteFileKind::CharDevice,
+        "b" => RemoteFileKind::BlockDevice,
+        "p" => RemoteFileKind::Pipe,
+        "s" => RemoteFileKind::Socket,
+        other => RemoteFileKind::Other(other.to_string()),
+      }, Permissions::parse(captures.at(2).unwrap())),
+    };
+    if permissions.is_none() {
+      return None;
+    }
+    let links = match fields[1].1.parse() {
+      Ok(links) => links,
+      Err(_)    => return None,
+    };
+
+    // the date takes 3 fields (month, day and year or time), and it is followed by the name
+    let date_at = match (4 .. fields.len() - 3).find(|&i| {
+      MONTHS.contains(&fields[i].1) && RE_DAY.is_match(fields[i + 1].1) && RE_YEAR_OR_TIME.is_match(fields[i + 2].1)
+    }) {
+      Some(date_at) => date_at,
+      None          => return None,
+    };
+
+    // after owner: group (optional) and size or `major, minor` device numbers
+    let middle = &fields[3 .. date_at];
+    let is_device = kind == RemoteFileKind::CharDevice || kind == RemoteFileKind::BlockDevice;
+    let (group, size_str) = match (is_device, middle.len()) {
+      (false, 1) => (None, middle[0].1),
+      (false, 2) => (Some(middle[0].1), middle[1].1),
+      (true, 1)  => (None, "0"),
+      (true, 2)  => if middle[0].1.ends_with(',') { (None, "0") } else { (Some(middle[0].1), "0") },
+      (true, 3)  => (Some(middle[0].1), "0"),
+      _          => return None,
+    };
+    let size = match size_str.parse() {
+      Ok(size) => size,
+      Err(_)   => return None,
+    };
+
+    let date_end = fields[date_at + 2].0 + fields[date_at + 2].1.len();
+    let date = &line[fields[date_at].0 .. date_end];
+    let name = &line[fields[date_at + 3].0 ..];
+    let (name, kind) = match kind {
+      RemoteFileKind::Symlink { .. } => match name.find(" -> ") {
+        Some(pos) => (&name[0 .. pos], RemoteFileKind::Symlink { target: name[pos + 4 ..].to_string() }),
+        None      => (name, kind),
+      },
+      _ => (name, kind),
+    };
+
+    Some(RemoteFile {
+      kind: kind,
+      size: size,
+      name: name.to_string(),
+      permissions: permissions,
+      links: Some(links),
+      owner: Some(fields[2].1.to_string()),
+      group: group.map(|group| group.to_string()),
+      date: Some(date.to_string()),
+      ..RemoteFile::default()
+    })
+  }
+
+  fn is_header(&self, line: &str) -> bool {
+    lazy_static! {
+      static ref RE_TOTAL: Regex = Regex::new("^total \\d+$").unwrap();
+    }
+    RE_TOTAL.is_match(line)
+  }
+}
+
+/// Parser of DOS-style (Windows/IIS) listing, e.g.
+/// `10-18-26  03:14PM       <DIR>          folder` or `07-19-2014  15:30   1830 my file.txt`.
+pub struct DosListParser;
+
+impl ListParser for DosListParser {
+
+  fn parse_line(&self, line: &str) -> Option<RemoteFile> {
+    lazy_static! {
+      static ref RE_DOS_LINE: Regex = Regex::new("^([0-9]{2}-[0-9]{2}-(?:[0-9]{2}|[0-9]{4}) +[0-9]{1,2}:[0-9]{2}(?:[AaPp][Mm])?) +(<DIR>|[0-9]+) +(.+)$").unwrap();
+    }
+    RE_DOS_LINE.captures(line).and_then(|captures|{
+      let date = captures.at(1).unwrap();
+      let name = captures.at(3).unwrap();
+      let (kind, size) = match captures.at(2).unwrap() {
+        "<DIR>"  => (RemoteFileKind::Directory, Ok(0)),
+        size_str => (RemoteFileKind::File, size_str.parse()),
+      };
+      size.ok().map(|size| RemoteFile {
+        kind: kind,
+        size: size,
+        name: name.to_string(),
+        date: Some(date.to_string()),
+        ..RemoteFile::default()
+      })
+    })
+  }
+}
+
+/// Parser of EPLF (Easily Parsed LIST Format), e.g. `+i8388621.48594,m825718503,r,s280,\tdjb.html`.
+pub struct EplfListParser;
+
+impl ListParser for EplfListParser {
+
+  fn parse_line(&self, line: &str) -> Option<RemoteFile> {
+    let tab = match line.find('\t') {
+      Some(tab) if line.starts_with('+') => tab,
+      _ => return None,
+    };
+    let name = &line[tab + 1 ..];
+    if name.is_empty() {
+      return None;
+    }
+    let mut file = RemoteFile { name: name.to_string(), ..RemoteFile::default() };
+    for fact in line[1 .. tab].split(',').filter(|fact| !fact.is_empty()) {
+      let mut chars = fact.chars();
+      let (tag, value) = (chars.next().unwrap(), chars.as_str());
+      match tag {
+        '/' => { file.kind = RemoteFileKind::Directory; },
+        's' => match value.parse() {
+          Ok(size) => { file.size = size; },
+          Err(_)   => return None,
+        },
+        'm' => match value.parse() {
+          Ok(seconds) => match Timestamp::from_unix_seconds(seconds) {
+            Some(modified) => { file.modified = Some(modified); },
+            None           => return None,
+          },
+          Err(_)      => return None,
+        },
+        'i' => { file.unique = Some(value.to_string()); },
+        'u' if value.starts_with('p') => {
+          file.unix_mode = u32::from_str_radix(&value[1 ..], 8).ok();
+          file.permissions = file.unix_mode.map(Permissions::from_mode);
+        },
+        _ => {},
+      }
+    }
+    Some(file)
+  }
+}
+
+/// Parser of OpenVMS listing, e.g.
+/// `CII-MANUAL.TEX;1  213/216  29-JAN-1996 03:33:12  [ANONYMOUS,ANONYMOUS]   (RWED,RWED,,)`.
+/// The version is stripped from the name, as well as `.DIR` extension of directories;
+/// the size is calculated from the count of used 512-bytes blocks.
+pub struct VmsListParser;
+
+impl ListParser for VmsListParser {
+
+  fn parse_line(&self, line: &str) -> Option<RemoteFile> {
+    lazy_static! {
+      static ref RE_VMS_LINE: Regex = Regex::new("^([^;\\s]+);[0-9]+\\s+([0-9]+)(?:/[0-9]+)?\\s+([0-9]{1,2}-[A-Za-z]{3}-[0-9]{4}\\s+[0-9]{1,2}:[0-9]{2}(?::[0-9]{2}(?:\\.[0-9]+)?)?)(?:\\s+\\[([^\\]]*)\\])?(?:\\s+(\\([^)]*\\)))?\\s*$").unwrap();
+    }
+    RE_VMS_LINE.captures(line).and_then(|captures|{
+      let name = captures.at(1).unwrap();
+      let (kind, name) = if name.to_uppercase().ends_with(".DIR") {
+        (RemoteFileKind::Directory, &name[0 .. name.len() - 4])
+      } else {
+        (RemoteFileKind::File, name)
+      };
+      let (group, owner) = match captures.at(4) {
+        Some(uic) => match uic.find(',') {
+          Some(pos) => (Some(uic[0 .. pos].to_string()), Some(uic[pos + 1 ..].to_string())),
+          None      => (None, Some(uic.to_string())),
+        },
+        None => (None, None),
+      };
+      let size = captures.at(2).unwrap().parse::<u64>().ok().and_then(|blocks| blocks.checked_mul(512));
+      size.map(|size| RemoteFile {
+        kind: kind,
+        size: size,
+        name: name.to_string(),
+        permissions: captures.at(5).and_then(Permissions::parse_vms),
+        owner: owner,
+        group: group,
+        date: Some(captures.at(3).unwrap().to_string()),
+        ..RemoteFile::default()
+      })
+    })
+  }
+
+  fn is_header(&self, line: &str) -> bool {
+    line.starts_with("Directory ") || line.starts_with("Total of ")
+  }
+}
+
+/// Parser of z/OS (MVS) datasets listing, e.g.
+/// `WYNK01 3390   2026/10/18  1   15  FB      80  3120  PO  USER.CNTL`.
+/// Partitioned datasets (`PO` organization) are reported as directories;
+/// the size is not known.
+pub struct MvsListParser;
+
+impl ListParser for MvsListParser {
+
+  fn parse_line(&self, line: &str) -> Option<RemoteFile> {
+    lazy_static! {
+      static ref RE_REFERRED: Regex = Regex::new("^(?:[0-9]{4}/[0-9]{2}/[0-9]{2}|\\*\\*NONE\\*\\*)$").unwrap();
+    }
+    let fields: Vec<&str> = line.split_whitespace().collect();
+    let (kind, name, date) = match (fields.len(), fields.first()) {
+      (2, Some(&"Migrated")) => (RemoteFileKind::File, fields[1], None),
+      (3, Some(&"Pseudo")) if fields[1] == "Directory" => (RemoteFileKind::Directory, fields[2], None),
+      (10, _) if RE_REFERRED.is_match(fields[2]) => {
+        let kind = if fields[8].starts_with("PO") { RemoteFileKind::Directory } else { RemoteFileKind::File };
+        let date = if fields[2].starts_with('*') { None } else { Some(fields[2].to_string()) };
+        (kind, fields[9], date)
+      },
+      _ => return None,
+    };
+    Some(RemoteFile {
+      kind: kind,
+      name: name.to_string(),
+      date: date,
+      ..RemoteFile::default()
+    })
+  }
+
+  fn is_header(&self, line: &str) -> bool {
+    line.starts_with("Volume ")
+  }
 }
 
 #[derive(PartialEq)]
@@ -131,6 +1158,20 @@ pub enum FtpError {
   GarbageData,
   /// Failed to authenticate.
   AuthFailed,
+  /// Server replied with error (4xx, 5xx) or unexpected code, and the text message.
+  ServerError(u32, String),
+  /// The command (`attempted`) is not allowed from the `current` state.
+  InvalidState { current: String, attempted: String },
+  /// The output buffer is too small, `needed` bytes are required to serialize the command.
+  BufferTooSmall { needed: usize },
+  /// Command argument contains CR, LF or NUL character, i.e. it would inject another command.
+  InvalidArgument,
+  /// Command argument can't be represented in the session encoding (see `set_encoding`).
+  UnencodableArgument,
+  /// The feature is not announced by the server in `FEAT` reply.
+  NotSupported(String),
+  /// None of listing parsers is able to parse the line.
+  UnparseableLine(String),
 }
 
 impl fmt::Display for FtpError {
@@ -140,20 +1181,245 @@ impl fmt::Display for FtpError {
       &FtpError::AuthFailed           => { write!(f, "[authorization failed]") }
       &FtpError::GarbageData          => { write!(f, "[garbage]") }
       &FtpError::ProtocolError(ref s) => { write!(f, "[protocol error: {}]", s) }
+      &FtpError::ServerError(code, ref s) => { write!(f, "[server error: {} {}]", code, s) }
+      &FtpError::InvalidState { ref current, ref attempted } => {
+        write!(f, "[{} is not allowed from the {}]", attempted, current)
+      }
+      &FtpError::BufferTooSmall { needed } => { write!(f, "[buffer too small: {} bytes needed]", needed) }
+      &FtpError::InvalidArgument      => { write!(f, "[invalid argument]") }
+      &FtpError::UnencodableArgument  => { write!(f, "[unencodable argument]") }
+      &FtpError::NotSupported(ref s)  => { write!(f, "[not supported: {}]", s) }
+      &FtpError::UnparseableLine(ref s) => { write!(f, "[unparseable line: {}]", s) }
+    }
+  }
+}
+
+/// Server reply: the code and all text lines, with the code prefixes stripped.
+/// Single-line reply has exactly one line.
+#[derive(Clone)]
+#[derive(PartialEq)]
+#[derive(Debug)]
+pub struct Reply {
+  pub code: u32,
+  pub lines: Vec<String>,
+}
+
+/// Returns parent of the absolute unix-like `path`, or `None` if it cannot be determined.
+fn parent_dir(path: &str) -> Option<String> {
+  if !path.starts_with('/') {
+    return None;
+  }
+  let trimmed = path.trim_right_matches('/');
+  match trimmed.rfind('/') {
+    Some(0) | None => Some("/".to_string()),
+    Some(pos)      => Some(trimmed[0 .. pos].to_string()),
+  }
+}
+
+/// Checks whether incomplete line might be the beginning of a reply, i.e. `NNN-` or `NNN `.
+fn is_reply_prefix(partial: &str) -> bool {
+  partial.bytes().take(4).enumerate().all(|(idx, b)| {
+    if idx < 3 { b >= b'0' && b <= b'9' } else { b == b' ' || b == b'-' || b == b'\r' }
+  })
+}
+
+/// Parses single (possibly multi-line, RFC 959) reply from the beginning of the `response`.
+/// Returns the reply and the count of consumed bytes.
+fn parse_reply(response: &str) -> Result<(Reply, usize), FtpError> {
+  lazy_static! {
+    static ref RE_REPLY_LINE: Regex = Regex::new("^([0-9]{3})(?:([ -])(.*))?$").unwrap();
+  }
+
+  let mut rest = response;
+  let mut consumed = 0;
+  // the reply, opened by `NNN-` line, along with its code as received (e.g. `099`)
+  let mut opened: Option<(Reply, &str)> = None;
+  loop {
+    let eol = match rest.find('\n') {
+      Some(pos) => pos,
+      None => {
+        return if opened.is_none() && !is_reply_prefix(rest) {
+          Err(FtpError::GarbageData)
+        } else {
+          Err(FtpError::NotEnoughData)
+        }
+      },
+    };
+    let line = rest[0 .. eol].trim_right_matches('\r');
+    consumed += eol + 1;
+    rest = &rest[eol + 1 ..];
+
+    match opened.take() {
+      None => {
+        let captures = match RE_REPLY_LINE.captures(line) {
+          Some(captures) => captures,
+          None           => return Err(FtpError::GarbageData),
+        };
+        let code:u32 = captures.at(1).unwrap().parse().unwrap();
+        let text = captures.at(3).unwrap_or("").to_string();
+        let reply = Reply { code: code, lines: vec![text] };
+        match captures.at(2) {
+          Some("-") => { opened = Some((reply, &line[0 .. 3])); },
+          _         => { return Ok((reply, consumed)); },
+        }
+      },
+      Some((mut reply, code_str)) => {
+        if line.starts_with(code_str) {
+          let tail = &line[3 ..];
+          if tail.is_empty() || tail.starts_with(' ') {
+            reply.lines.push(tail.trim_left_matches(' ').to_string());
+            return Ok((reply, consumed));
+          }
+          if tail.starts_with('-') {
+            reply.lines.push(tail[1 ..].to_string());
+            opened = Some((reply, code_str));
+            continue;
+          }
+        }
+        // continuation line without code prefix, might start with other digits
+        reply.lines.push(line.to_string());
+        opened = Some((reply, code_str));
+      },
     }
   }
 }
 
+/// Parses single listing line by the first suitable parser, and its date column
+/// (relatively to `now`); returns `None` for empty lines and headers.
+fn parse_list_line(parsers: &[Rc<dyn ListParser>], line: &str, now: &Timestamp) -> Option<Result<RemoteFile, FtpError>> {
+  if line.trim().is_empty() {
+    return None;
+  }
+  match parsers.iter().filter_map(|parser| parser.parse_line(line)).next() {
+    Some(mut file) => {
+      if file.modified.is_none() {
+        file.modified = file.date.as_ref().and_then(|date| Timestamp::parse_list_date(date, now));
+      }
+      Some(Ok(file))
+    },
+    None if parsers.iter().any(|parser| parser.is_header(line)) => None,
+    None => Some(Err(FtpError::UnparseableLine(line.to_string()))),
+  }
+}
+
+/// Incremental parser of `LIST` output (see `FtpTransmitter::list_stream`): the data
+/// is fed by chunks as it comes from data connection, and the parsed files are taken
+/// via `files` (or `next_file`). Only incomplete line is kept between chunks, so, to have
+/// bounded memory, all the available files should be taken after each chunk.
+pub struct ListStream {
+  parsers: Vec<Rc<dyn ListParser>>,
+  encoding: Encoding,
+  now: Timestamp,
+  pending: Vec<u8>,
+  consumed: usize,
+  finished: bool,
+}
+
+impl ListStream {
+
+  /// Appends the next chunk of listing.
+  pub fn feed(&mut self, data: &[u8]) {
+    self.pending.drain(.. self.consumed);
+    self.consumed = 0;
+    self.pending.extend_from_slice(data);
+  }
+
+  /// Marks the end of listing (i.e. data connection has been closed), so the last
+  /// line is parsed even if it lacks line ending.
+  pub fn finish(&mut self) {
+    self.finished = true;
+  }
+
+  /// Returns iterator over the files available so far (parsed from the complete lines),
+  /// e.g. to be drained after each `feed`.
+  pub fn files<'a>(&'a mut self) -> ListStreamFiles<'a> {
+    ListStreamFiles { stream: self }
+  }
+
+  /// Returns the next parsed file, or `None` if more data is needed (or the listing is over);
+  /// i.e. it might return files again after the next `feed`.
+  pub fn next_file(&mut self) -> Option<Result<RemoteFile, FtpError>> {
+    loop {
+      let rest = &self.pending[self.consumed ..];
+      let (line_end, next_start) = match rest.iter().position(|&b| b == b'\n') {
+        Some(pos) => (pos, pos + 1),
+        None if self.finished && !rest.is_empty() => (rest.len(), rest.len()),
+        None => return None,
+      };
+      let encoding = self.encoding;
+      let line = encoding.decode_escaped(&rest[0 .. line_end]);
+      let item = parse_list_line(&self.parsers, line.trim_right_matches('\r'), &self.now)
+        .map(|result| match result {
+          Ok(file) => Ok(file.unescaped(&encoding)),
+          Err(FtpError::UnparseableLine(line)) => Err(FtpError::UnparseableLine(encoding.unescaped(&line))),
+          Err(error) => Err(error),
+        });
+      self.consumed += next_start;
+      if item.is_some() {
+        return item;
+      }
+    }
+  }
+}
+
+/// Iterator over the files available in `ListStream` (see `ListStream::files`).
+pub struct ListStreamFiles<'a> {
+  stream: &'a mut ListStream,
+}
+
+impl<'a> Iterator for ListStreamFiles<'a> {
+  type Item = Result<RemoteFile, FtpError>;
+
+  fn next(&mut self) -> Option<Self::Item> {
+    self.stream.next_file()
+  }
+}
+
 struct FtpInternals {
   error: Option<FtpError>,
   data_mode: Option<DataMode>,
   working_dir: Option<String>,
+  created_dir: Option<String>,
   sent_request: Option<Rc<State>>,
   system: Option<(String, String)>,
-  endpoint: Option<(Ipv4Addr, u16)>,
+  features: Option<Features>,
+  file_size: Option<u64>,
+  modification_time: Option<Timestamp>,
+  file_facts: Option<RemoteFile>,
+  endpoint: Option<SocketAddr>,
+  rest_offset: Option<u64>,
+  reply: Option<Reply>,
+  pending: Vec<u8>,
+  encoding: Encoding,
+  list_parsers: Vec<Rc<dyn ListParser>>,
   state: Rc<State>,
 }
 
+impl FtpInternals {
+
+  /// Parses the reply from the beginning of not consumed bytes and drops the parsed ones.
+  /// In the case of `FtpError::GarbageData` all not consumed bytes are dropped.
+  fn take_reply(&mut self) -> Result<Reply, FtpError> {
+    let encoding = self.encoding;
+    let parse_result = FtpReceiver::decode_pending(&self.pending, &encoding)
+      .and_then(|response|{
+        parse_reply(&response).map(|(reply, consumed)| (reply, encoding.encoded_len(&response[0 .. consumed])))
+      });
+    match parse_result {
+      Ok((reply, consumed)) => {
+        self.pending.drain(0 .. consumed);
+        Ok(reply)
+      },
+      Err(e) => {
+        if e == FtpError::GarbageData {
+          self.pending.clear();
+        }
+        Err(e)
+      },
+    }
+  }
+}
+
 /// "Passive" side of FTP protocol, which mean that receiver expects
 /// some data from remote server. As soon as it receives enough data
 /// it can "advance" to transmitter state, i.e. fill buffer with
@@ -164,6 +1430,10 @@ pub struct FtpReceiver {
 
 /// "Active" side of FTP protocol, i.e. fill buffer with desired
 /// FTP commands for further delivery to remote server.
+///
+/// If a command is not allowed in the current state, nothing is written and
+/// the unmodified `Transmitter` is returned as the error; the actually happened
+/// error (`FtpError::InvalidState`) can be obtained via `take_error`.
 pub struct FtpTransmitter {
   internals: Rc<FtpInternals>
 }
@@ -176,6 +1446,15 @@ impl fmt::Debug for FtpError {
             &FtpError::NotEnoughData          => write!(f, "no enough data"),
             &FtpError::ProtocolError(ref err) => write!(f, "protocol error: {}", err),
             &FtpError::AuthFailed             => write!(f, "authentication failed"),
+            &FtpError::ServerError(code, ref text) => write!(f, "server error: {} {}", code, text),
+            &FtpError::InvalidState { ref current, ref attempted } => {
+              write!(f, "invalid state: {} is not allowed from the {}", attempted, current)
+            }
+            &FtpError::BufferTooSmall { needed } => write!(f, "buffer too small: {} bytes needed", needed),
+            &FtpError::InvalidArgument        => write!(f, "invalid argument"),
+            &FtpError::UnencodableArgument    => write!(f, "unencodable argument"),
+            &FtpError::NotSupported(ref feature) => write!(f, "not supported: {}", feature),
+            &FtpError::UnparseableLine(ref line) => write!(f, "unparseable line: {}", line),
         }
     }
 }
@@ -187,120 +1466,237 @@ impl FtpReceiver {
         error: None,
         data_mode: None,
         working_dir: None,
+        created_dir: None,
         sent_request: None,
         system: None,
+        features: None,
+        file_size: None,
+        modification_time: None,
+        file_facts: None,
         endpoint: None,
+        rest_offset: None,
+        reply: None,
+        pending: Vec::new(),
+        encoding: Encoding::Utf8,
+        list_parsers: Vec::new(),
         state: Rc::new(State::NonAuthorized),
       })
     }
   }
 
 
-  fn advance_state(prev_state: &State, prev_req: &Option<Rc<State>>, bytes: &[u8]) -> Result<State, FtpError> {
+  fn advance_state(prev_state: &State, prev_req: &Option<Rc<State>>, reply: &Reply) -> Result<State, FtpError> {
 
     lazy_static! {
-      static ref RE_RESPONCE_CODE: Regex = Regex::new("(?m:^(\\d{3}) (.+)\r$)").unwrap();
       static ref RE_PATHNAME: Regex = Regex::new("\"(.+)\"").unwrap();
-      static ref RE_SYSTEM: Regex = Regex::new("(\\w+) [Tt]ype: (\\w+)").unwrap();
-      static ref RE_PARTRIAL_RESPONCE_CODE: Regex = Regex::new("(?m:^(\\d{3})-.+\r$)").unwrap();
+      static ref RE_SYSTEM: Regex = Regex::new("(\\w+)(?: [Tt]ype: (\\w+))?").unwrap();
       static ref RE_PASSIVE_MODE: Regex = Regex::new("Entering Passive Mode \\((\\d+),(\\d+),(\\d+),(\\d+),(\\d+),(\\d+)\\)").unwrap();
+      static ref RE_EXTENDED_PASSIVE_MODE: Regex = Regex::new("\\(([!-/:-~])([!-/:-~])([!-/:-~])(\\d+)([!-/:-~])\\)").unwrap();
     }
 
-    str::from_utf8(bytes)
-      .map_err(|_| FtpError::GarbageData)
-      .and_then(|response|
-        RE_RESPONCE_CODE.captures(&response)
-          .ok_or_else(||{
-            if RE_PARTRIAL_RESPONCE_CODE.is_match(response) {
-              FtpError::NotEnoughData
-            } else {
-              FtpError::GarbageData
+    let text = reply.lines[0].as_str();
+    let code = reply.code;
+    let transition = match code {
+      LOGGED_EXPECTED          => Ok(State::LoginReady),
+      PASSWORD_EXPECTED        => Ok(State::PasswordExpected),
+      LOGGED_IN                => Ok(State::Authorized),
+      AUTHENTICATION_FAILED    => Err(FtpError::AuthFailed),
+      OPENNING_DATA_CONNECTION => Ok(State::DataTransferStarted),
+      DATA_CONNECTION_OPENED   => Ok(State::DataTransferStarted),
+      CLOSING_DATA_CONNECTION  => Ok(State::DataTransferCompleted),
+      FILE_ACTION_OK => {
+        match &*prev_req {
+          &Some(ref prev_sent_req) => {
+            match &**prev_sent_req {
+              &State::CwdReqSent(_)  => Ok(State::CwdConfirmed),
+              &State::RmdReqSent(_)  => Ok(State::DirectoryRemoved),
+              &State::CdupReqSent    => Ok(State::CdupConfirmed),
+              &State::DeleReqSent(_) => Ok(State::FileDeleted),
+              &State::RenameToReqSent(_) => Ok(State::FileRenamed),
+              // the facts are sent as the single line between the first and the last reply lines
+              &State::MlstReqSent(_) if reply.lines.len() > 2 => {
+                let entry = &reply.lines[1];
+                let entry = if entry.starts_with(' ') { &entry[1 ..] } else { &entry[..] };
+                RemoteFile::parse_facts(entry).map(|file| State::FileFactsReceived(file))
+              },
+              _ => Err(FtpError::GarbageData),
+            }
+          },
+          _ => Err(FtpError::GarbageData),
+        }
+      },
+      // e.g. UTF-8 is always on
+      COMMAND_SUPERFLUOUS => {
+        match &*prev_req {
+          &Some(ref prev_sent_req) => {
+            match &**prev_sent_req {
+              &State::Utf8ReqSent => Ok(State::Utf8Enabled),
+              _ => Err(FtpError::GarbageData),
+            }
+          },
+          _ => Err(FtpError::GarbageData),
+        }
+      },
+      OPERATION_SUCCESS  => {
+        match &*prev_req {
+          &Some(ref prev_sent_req) => {
+            match &**prev_sent_req {
+              &State::DataTypeReqSent(ref value) => Ok(State::DataTypeConfirmed(value.clone())),
+              &State::ActiveReqSent              => Ok(State::ActiveConfirmed),
+              &State::Utf8ReqSent                => Ok(State::Utf8Enabled),
+              &State::CdupReqSent                => Ok(State::CdupConfirmed),
+              _ => Err(FtpError::GarbageData),
+            }
+          },
+          _ => Err(FtpError::GarbageData),
+        }
+      },
+      PENDING_FURTHER_INFO => {
+        match &*prev_req {
+          &Some(ref prev_sent_req) => {
+            match &**prev_sent_req {
+              &State::RestReqSent(offset) => Ok(State::RestConfirmed(offset)),
+              &State::RenameFromReqSent(ref path) => Ok(State::RenameFromConfirmed(path.clone())),
+              _ => Err(FtpError::GarbageData),
+            }
+          },
+          _ => Err(FtpError::GarbageData),
+        }
+      },
+      PATHNAME_AVAILABLE => {
+        RE_PATHNAME.captures(text)
+          .ok_or(FtpError::GarbageData)
+          .and_then(|path_capture|{
+            let path = path_capture.at(1).unwrap().to_string();
+            match &*prev_req {
+              &Some(ref prev_sent_req) => {
+                match &**prev_sent_req {
+                  &State::MkdReqSent(_) => Ok(State::DirectoryCreated(path)),
+                  _                     => Ok(State::PathReceived(path)),
+                }
+              },
+              _ => Ok(State::PathReceived(path)),
             }
           })
-          .and_then(|captures| {
-            let code_str = captures.at(1).unwrap();
-            let code:u32 = code_str.parse().unwrap();
-            match code {
-              LOGGED_EXPECTED          => Ok(State::LoginReady),
-              PASSWORD_EXPECTED        => Ok(State::PasswordExpected),
-              LOGGED_IN                => Ok(State::Authorized),
-              AUTHENTICATION_FAILED    => Err(FtpError::AuthFailed),
-              OPENNING_DATA_CONNECTION => Ok(State::DataTransferStarted),
-              CLOSING_DATA_CONNECTION  => Ok(State::DataTransferCompleted),
-              CWD_CONFIRMED            => Ok(State::CwdConfirmed),
-              OPERATION_SUCCESS  => {
-                match &*prev_req {
-                  &Some(ref prev_sent_req) => {
-                    match &**prev_sent_req {
-                      &State::DataTypeReqSent(ref value) => Ok(State::DataTypeConfirmed(value.clone())),
-                      _ => Err(FtpError::GarbageData),
-                    }
-                  },
-                  _ => Err(FtpError::GarbageData),
-                }
+      },
+      FILE_STATUS => {
+        match &*prev_req {
+          &Some(ref prev_sent_req) => {
+            match &**prev_sent_req {
+              &State::SizeReqSent(_) => {
+                text.trim().parse::<u64>()
+                  .map(|size| State::SizeReceived(size))
+                  .map_err(|_| FtpError::GarbageData)
               },
-              PATHNAME_AVAILABLE => {
-                let pathname_str = captures.at(2).unwrap();
-                RE_PATHNAME.captures(pathname_str)
+              &State::MdtmReqSent(_) => {
+                Timestamp::parse_time_val(text.trim())
+                  .map(|time| State::ModificationTimeReceived(time))
                   .ok_or(FtpError::GarbageData)
-                  .and_then(|path_capture|{
-                    let path = path_capture.at(1).unwrap();
-                    Ok(State::PathReceived(path.to_string()))
-                  })
               },
-              SYSTEM_RECEIVED => {
-                let system_str = captures.at(2).unwrap();
-                RE_SYSTEM.captures(system_str)
-                  .ok_or(FtpError::GarbageData)
-                  .and_then(|path_capture|{
-                    let name = path_capture.at(1).unwrap();
-                    let subtype = path_capture.at(2).unwrap();
-                    Ok(State::SystemRecived(name.to_string(), subtype.to_string()))
+              _ => Err(FtpError::GarbageData),
+            }
+          },
+          _ => Err(FtpError::GarbageData),
+        }
+      },
+      SYSTEM_STATUS => {
+        match &*prev_req {
+          &Some(ref prev_sent_req) => {
+            match &**prev_sent_req {
+              &State::FeatReqSent => Ok(State::FeaturesReceived(Features::parse(reply))),
+              _ => Err(FtpError::GarbageData),
+            }
+          },
+          _ => Err(FtpError::GarbageData),
+        }
+      },
+      SYSTEM_RECEIVED => {
+        RE_SYSTEM.captures(text)
+          .ok_or(FtpError::GarbageData)
+          .and_then(|path_capture|{
+            let name = path_capture.at(1).unwrap();
+            // IIS replies just `215 Windows_NT`
+            let subtype = path_capture.at(2).unwrap_or("");
+            Ok(State::SystemRecived(name.to_string(), subtype.to_string()))
+          })
+      },
+      PASSIVE_MODE => {
+        RE_PASSIVE_MODE.captures(text)
+          .ok_or(FtpError::GarbageData)
+          .and_then(|path_capture|{
+            path_capture.iter().skip(1)
+              .map(|opt_value| opt_value.unwrap().parse::<u8>())
+              .collect::<Result<Vec<u8>, _>>()
+              .map_err(|_| FtpError::GarbageData)
+          })
+          .map(|numbers|{
+            let (a, b, c, d) = (numbers[0], numbers[1], numbers[2], numbers[3]);
+            let p1_16 = numbers[4] as u16;
+            let p2_16 = numbers[5] as u16;
+
+            let addr = Ipv4Addr::new(a, b, c, d);
+            let port = 256 * p1_16 + p2_16;
+            State::PassiveConfirmed(SocketAddr::V4(SocketAddrV4::new(addr, port)))
+          })
+      }
+      EXTENDED_PASSIVE_MODE => {
+        match &*prev_req {
+          &Some(ref prev_sent_req) => {
+            match &**prev_sent_req {
+              &State::ExtendedPassiveReqSent(ref peer) => {
+                RE_EXTENDED_PASSIVE_MODE.captures(text)
+                  .and_then(|port_capture| {
+                    // all four delimiters must be the same character
+                    let delimiter = port_capture.at(1).unwrap();
+                    let same = [2, 3, 5].iter().all(|&i| port_capture.at(i).unwrap() == delimiter);
+                    if same { port_capture.at(4).unwrap().parse::<u16>().ok() } else { None }
                   })
-              },
-              PASSIVE_MODE => {
-                let addr_str = captures.at(2).unwrap();
-                RE_PASSIVE_MODE.captures(addr_str)
                   .ok_or(FtpError::GarbageData)
-                  .and_then(|path_capture|{
-                    let mut numbers = path_capture.iter().skip(1).map(|opt_value| {
-                      let value = opt_value.unwrap();
-                      let number:u8 = value.parse().unwrap();
-                      number
-                    });
-                    let a = numbers.next().unwrap();
-                    let b = numbers.next().unwrap();
-                    let c = numbers.next().unwrap();
-                    let d = numbers.next().unwrap();
-                    let p1 = numbers.next().unwrap();
-                    let p2 = numbers.next().unwrap();
-
-                    let p1_16 = p1 as u16;
-                    let p2_16 = p2 as u16;
-
-                    let addr = Ipv4Addr::new(a, b, c, d);
-                    let port = 256 * p1_16 + p2_16;
-                    Ok(State::PassiveConfirmed(addr, port))
-                  })
-              }
-              _ => panic!(format!("unknown responce: {}", response))
+                  .map(|port| State::PassiveConfirmed(SocketAddr::new(peer.clone(), port)))
+              },
+              _ => Err(FtpError::GarbageData),
             }
-          })
-      )
+          },
+          _ => Err(FtpError::GarbageData),
+        }
+      }
+      _ => Err(FtpError::ServerError(code, reply.lines.join("\n"))),
+    };
+
+    transition
       .and_then(|new_state|{
         let allowed:bool = match (prev_state, &new_state) {
           (&State::NonAuthorized, &State::LoginReady)                  => true,
           (&State::LoginReqSent, &State::PasswordExpected)             => true,
+          (&State::LoginReqSent, &State::Authorized)                   => true,
           (&State::PasswordExpected, &State::PasswordReqSent)          => true,
           (&State::PasswordReqSent, &State::Authorized)                => true,
           (&State::PwdReqSent, &State::PathReceived(_))                => true,
           (&State::DataTypeReqSent(_), &State::DataTypeConfirmed(_))   => true,
           (&State::SystemReqSent, &State::SystemRecived(_, _))         => true,
-          (&State::PassiveReqSent, &State::PassiveConfirmed(_, _))     => true,
+          (&State::FeatReqSent, &State::FeaturesReceived(_))           => true,
+          (&State::Utf8ReqSent, &State::Utf8Enabled)                   => true,
+          (&State::SizeReqSent(_), &State::SizeReceived(_))            => true,
+          (&State::MdtmReqSent(_), &State::ModificationTimeReceived(_)) => true,
+          (&State::PassiveReqSent, &State::PassiveConfirmed(_))        => true,
+          (&State::ExtendedPassiveReqSent(_), &State::PassiveConfirmed(_)) => true,
+          (&State::ActiveReqSent, &State::ActiveConfirmed)             => true,
+          (&State::MlstReqSent(_), &State::FileFactsReceived(_))       => true,
           (&State::ListReqSent, &State::DataTransferStarted)           => true,
+          (&State::NlstReqSent, &State::DataTransferStarted)           => true,
+          (&State::MlsdReqSent, &State::DataTransferStarted)           => true,
           (&State::FileReqSent, &State::DataTransferStarted)           => true,
+          (&State::FilePutReqSent, &State::DataTransferStarted)        => true,
+          (&State::FileAppendReqSent, &State::DataTransferStarted)     => true,
+          (&State::RestReqSent(_), &State::RestConfirmed(_))           => true,
           (&State::DataTransferStarted, &State::DataTransferCompleted) => true,
           (&State::CwdReqSent(_), &State::CwdConfirmed)                => true,
+          (&State::CdupReqSent, &State::CdupConfirmed)                 => true,
+          (&State::MkdReqSent(_), &State::DirectoryCreated(_))         => true,
+          (&State::RmdReqSent(_), &State::DirectoryRemoved)            => true,
+          (&State::DeleReqSent(_), &State::FileDeleted)                => true,
+          (&State::RenameFromReqSent(_), &State::RenameFromConfirmed(_)) => true,
+          (&State::RenameToReqSent(_), &State::FileRenamed)            => true,
           _ => false,
         };
         if allowed {
@@ -313,22 +1709,71 @@ impl FtpReceiver {
   }
 
 
+  /// Returns the state, from which the next command can be sent after the
+  /// request has been rejected by the server.
+  fn recover_state(state: &State) -> State {
+    match state {
+      &State::NonAuthorized => State::NonAuthorized,
+      &State::LoginReady | &State::LoginReqSent |
+      &State::PasswordExpected | &State::PasswordReqSent => State::LoginReady,
+      _ => State::Authorized,
+    }
+  }
+
+  /// Decodes the received bytes; the incomplete UTF-8 sequence at the end is left
+  /// for the further data.
+  fn decode_pending(pending: &[u8], encoding: &Encoding) -> Result<String, FtpError> {
+    match encoding {
+      &Encoding::Utf8 => match str::from_utf8(pending) {
+        Ok(response) => Ok(response.to_string()),
+        Err(e) => match e.error_len() {
+          None    => Ok(str::from_utf8(&pending[0 .. e.valid_up_to()]).unwrap().to_string()),
+          Some(_) => Err(FtpError::GarbageData),
+        },
+      },
+      _ => Ok(encoding.decode(pending).unwrap()),
+    }
+  }
+
   /// Try to consume `Receiver` by parsing buffer and advance into `Transmitter`.
+  /// The `buffer` should contain only newly received bytes: they are appended to
+  /// the ones, which have not been consumed yet (incomplete reply, or the next reply
+  /// received in the same chunk). The bytes after the parsed reply are kept for the
+  /// next `try_advance` invocation, i.e. it can be invoked with empty buffer.
+  ///
   /// In the case of an error, it returns unmodified `Receiver` as the error. The
-  /// actually happened error can be obtained via `take_error`.
+  /// actually happened error can be obtained via `take_error`. If the server rejected
+  /// the request (`FtpError::ServerError`), the `Receiver` can be turned into
+  /// `Transmitter` via `to_transmitter` to send other commands. In the case of
+  /// `FtpError::GarbageData` all not consumed bytes are dropped.
   ///
   /// In case of success it remembers the last successful state, probably switches
   /// it and returns `Transmitter` object.
   pub fn try_advance(self, buffer: &[u8]) -> Result<FtpTransmitter, Self> {
     let mut internals = self.internals;
+    Rc::get_mut(&mut internals).unwrap().pending.extend_from_slice(buffer);
 
-    let transition_result = FtpReceiver::advance_state(&internals.state, &internals.sent_request, buffer);
+    let parse_result = Rc::get_mut(&mut internals).unwrap().take_reply();
+    let transition_result = parse_result.and_then(|reply|{
+      let transition = FtpReceiver::advance_state(&internals.state, &internals.sent_request, &reply);
+      Rc::get_mut(&mut internals).unwrap().reply = Some(reply);
+      transition
+    });
 
     match transition_result {
       Err(e) => {
         println!("error on state: {}", internals.state);
-        if &e == &FtpError::AuthFailed {
-          Rc::get_mut(&mut internals).unwrap().state = Rc::new(State::LoginReady);
+        match &e {
+          &FtpError::AuthFailed => {
+            Rc::get_mut(&mut internals).unwrap().state = Rc::new(State::LoginReady);
+          },
+          &FtpError::ServerError(_, _) => {
+            let int_ref = Rc::get_mut(&mut internals).unwrap();
+            int_ref.state = Rc::new(FtpReceiver::recover_state(&int_ref.state));
+            int_ref.sent_request = None;
+            int_ref.rest_offset = None;
+          },
+          _ => {},
         }
         Rc::get_mut(&mut internals).unwrap().error = Some(e);
         Err(FtpReceiver { internals: internals.clone() })
@@ -352,8 +1797,32 @@ impl FtpReceiver {
               int_ref.system = Some((name, subtype));
               State::Authorized
             }
-            State::PassiveConfirmed(addr, port) => {
-              int_ref.endpoint = Some((addr, port));
+            State::FeaturesReceived(features) => {
+              int_ref.features = Some(features);
+              State::Authorized
+            }
+            State::SizeReceived(size) => {
+              int_ref.file_size = Some(size);
+              State::Authorized
+            }
+            State::ModificationTimeReceived(time) => {
+              int_ref.modification_time = Some(time);
+              State::Authorized
+            }
+            State::FileFactsReceived(mut file) => {
+              file.raw_name = int_ref.encoding.encode(&file.name).unwrap_or_default();
+              int_ref.file_facts = Some(file);
+              State::Authorized
+            }
+            State::Utf8Enabled => {
+              int_ref.encoding = Encoding::Utf8;
+              State::Authorized
+            }
+            State::PassiveConfirmed(addr) => {
+              int_ref.endpoint = Some(addr);
+              State::Authorized
+            }
+            State::ActiveConfirmed => {
               State::Authorized
             }
             State::CwdConfirmed => {
@@ -363,7 +1832,23 @@ impl FtpReceiver {
               };
               State::Authorized
             }
+            State::CdupConfirmed => {
+              int_ref.working_dir = int_ref.working_dir.take().and_then(|path| parent_dir(&path));
+              State::Authorized
+            }
+            State::DirectoryCreated(path) => {
+              int_ref.created_dir = Some(path);
+              State::Authorized
+            }
+            State::DirectoryRemoved | State::FileDeleted | State::FileRenamed => {
+              State::Authorized
+            }
+            State::RestConfirmed(offset) => {
+              int_ref.rest_offset = Some(offset);
+              State::RestConfirmed(offset)
+            }
             State::DataTransferCompleted => {
+              int_ref.rest_offset = None;
               State::Authorized
             }
             _ => new_state,
@@ -383,6 +1868,16 @@ impl FtpReceiver {
     Rc::get_mut(&mut self.internals).unwrap().error.take()
   }
 
+  /// Returns the last reply received from the server (including error replies).
+  pub fn get_last_reply(&self) -> Option<&Reply> {
+    self.internals.reply.as_ref()
+  }
+
+  /// Sets up text encoding of the session (`Encoding::Utf8` by default).
+  pub fn set_encoding(&mut self, encoding: Encoding) {
+    Rc::get_mut(&mut self.internals).unwrap().encoding = encoding;
+  }
+
   /// Sometimes you need to manually advance to `Transmitter`
   /// e.g. in case of Authorization Error, you can re-send
   /// other credentials.
@@ -398,18 +1893,137 @@ lazy_static! {
   static ref DATA_PASS: &'static [u8]        = "PASS ".as_bytes();
   static ref DATA_PWD: &'static [u8]         = "PWD\r\n".as_bytes();
   static ref DATA_ENDING: &'static [u8]      = "\r\n".as_bytes();
+  static ref DATA_SPACE: &'static [u8]       = " ".as_bytes();
   static ref DATA_DATA_BINARY: &'static [u8] = "TYPE I\r\n".as_bytes();
   static ref DATA_DATA_TEXT: &'static [u8]   = "TYPE T\r\n".as_bytes();
   static ref DATA_SYST: &'static [u8]        = "SYST\r\n".as_bytes();
+  static ref DATA_FEAT: &'static [u8]        = "FEAT\r\n".as_bytes();
+  static ref DATA_OPTS_UTF8: &'static [u8]   = "OPTS UTF8 ON\r\n".as_bytes();
+  static ref DATA_SIZE: &'static [u8]        = "SIZE ".as_bytes();
+  static ref DATA_MDTM: &'static [u8]        = "MDTM ".as_bytes();
   static ref DATA_PASV: &'static [u8]        = "PASV\r\n".as_bytes();
-  static ref DATA_LIST: &'static [u8]        = "LIST -l\r\n".as_bytes();
+  static ref DATA_EPSV: &'static [u8]        = "EPSV\r\n".as_bytes();
+  static ref DATA_PORT: &'static [u8]        = "PORT ".as_bytes();
+  static ref DATA_EPRT: &'static [u8]        = "EPRT ".as_bytes();
+  static ref DATA_LIST: &'static [u8]        = "LIST".as_bytes();
+  static ref DATA_NLST: &'static [u8]        = "NLST".as_bytes();
+  static ref DATA_MLSD: &'static [u8]        = "MLSD".as_bytes();
+  static ref DATA_MLST: &'static [u8]        = "MLST".as_bytes();
   static ref DATA_CWD:  &'static [u8]        = "CWD ".as_bytes();
+  static ref DATA_CDUP: &'static [u8]        = "CDUP\r\n".as_bytes();
+  static ref DATA_MKD:  &'static [u8]        = "MKD ".as_bytes();
+  static ref DATA_RMD:  &'static [u8]        = "RMD ".as_bytes();
+  static ref DATA_DELE: &'static [u8]        = "DELE ".as_bytes();
+  static ref DATA_RNFR: &'static [u8]        = "RNFR ".as_bytes();
+  static ref DATA_RNTO: &'static [u8]        = "RNTO ".as_bytes();
   static ref DATA_RETR: &'static [u8]        = "RETR ".as_bytes();
+  static ref DATA_STOR: &'static [u8]        = "STOR ".as_bytes();
+  static ref DATA_APPE: &'static [u8]        = "APPE ".as_bytes();
+  static ref DATA_REST: &'static [u8]        = "REST ".as_bytes();
 }
 
 
 impl FtpTransmitter {
 
+  /// Remembers the error and returns unmodified `Transmitter`.
+  fn fail(mut self, error: FtpError) -> Self {
+    Rc::get_mut(&mut self.internals).unwrap().error = Some(error);
+    self
+  }
+
+  /// Remembers `FtpError::InvalidState` error and returns unmodified `Transmitter`.
+  fn invalid_state(self, attempted: &str) -> Self {
+    let current = format!("{}", self.internals.state);
+    self.fail(FtpError::InvalidState { current: current, attempted: attempted.to_string() })
+  }
+
+  /// Checks that the `feature` is announced by the server, if the features are known
+  /// (see `send_feat_req`); otherwise remembers `FtpError::NotSupported` error.
+  fn check_feature(self, feature: &str, supported: fn(&Features) -> bool) -> Result<Self, Self> {
+    let known_unsupported = match &self.internals.features {
+      &Some(ref features) => !supported(features),
+      &None               => false,
+    };
+    if known_unsupported {
+      Err(self.fail(FtpError::NotSupported(feature.to_string())))
+    } else {
+      Ok(self)
+    }
+  }
+
+  /// Serializes command `parts` into the output buffer, modifies `count` variable with
+  /// the count of written bytes and advances into `Receiver`, which expects reply
+  /// for the `sent_request`. Nothing is written if the buffer is too small.
+  fn send_command(self, buffer: &mut [u8], count: &mut usize, parts: &[&[u8]], sent_request: State) -> Result<FtpReceiver, Self> {
+    let needed = parts.iter().fold(0, |acc, part| acc + part.len());
+    if buffer.len() < needed {
+      return Err(self.fail(FtpError::BufferTooSmall { needed: needed }));
+    }
+
+    let mut my_count = 0;
+    for part in parts {
+      buffer[my_count .. my_count + part.len()].copy_from_slice(part);
+      my_count += part.len();
+    }
+    *count = my_count;
+
+    let mut internals = self.internals;
+    {
+      let int_ref = Rc::get_mut(&mut internals).unwrap();
+      int_ref.state = Rc::new(sent_request);
+      int_ref.sent_request = Some(int_ref.state.clone());
+    }
+    Ok(FtpReceiver { internals: internals })
+  }
+
+  /// Serializes `command` with user supplied `argument` (see `send_command`). The argument
+  /// is refused if it contains CR, LF or NUL characters, or it can't be encoded.
+  fn send_command_arg(self, buffer: &mut [u8], count: &mut usize, command: &[u8], argument: &str, sent_request: State) -> Result<FtpReceiver, Self> {
+    match self.encode_argument(argument) {
+      Ok(argument) => self.send_command(buffer, count, &[command, &argument, &DATA_ENDING], sent_request),
+      Err(error)   => Err(self.fail(error)),
+    }
+  }
+
+  /// Encodes command argument in the session encoding; fails if it contains CR, LF
+  /// or NUL character, or it can't be encoded.
+  fn encode_argument(&self, argument: &str) -> Result<Vec<u8>, FtpError> {
+    if argument.bytes().any(|b| b == b'\r' || b == b'\n' || b == 0) {
+      return Err(FtpError::InvalidArgument);
+    }
+    self.internals.encoding.encode(argument).ok_or(FtpError::UnencodableArgument)
+  }
+
+  /// Same as `send_command_arg`, but the `command` (without trailing space) is sent
+  /// alone, if there is no `argument`.
+  fn send_command_opt_arg(self, buffer: &mut [u8], count: &mut usize, command: &[u8], argument: Option<&str>, sent_request: State) -> Result<FtpReceiver, Self> {
+    match argument.map(|argument| self.encode_argument(argument)) {
+      Some(Ok(argument)) => self.send_command(buffer, count, &[command, &DATA_SPACE, &argument, &DATA_ENDING], sent_request),
+      Some(Err(error))   => Err(self.fail(error)),
+      None               => self.send_command(buffer, count, &[command, &DATA_ENDING], sent_request),
+    }
+  }
+
+  /// Returns tha last occurred error, and internally
+  /// sets up `None`.
+  pub fn take_error(&mut self) -> Option<FtpError> {
+    Rc::get_mut(&mut self.internals).unwrap().error.take()
+  }
+
+  /// Parses the reply, received when no command has been sent (e.g. `421` before the server
+  /// closes connection), from the `buffer` appended to the not consumed bytes. Unlike
+  /// `FtpReceiver::try_advance`, the state is kept as is.
+  fn take_unsolicited_reply(&mut self, buffer: &[u8]) -> Result<Reply, FtpError> {
+    let int_ref = Rc::get_mut(&mut self.internals).unwrap();
+    int_ref.pending.extend_from_slice(buffer);
+    int_ref.take_reply()
+  }
+
+  /// Returns the last reply received from the server.
+  pub fn get_last_reply(&self) -> Option<&Reply> {
+    self.internals.reply.as_ref()
+  }
+
   /// Sometimes you need to manually advance to `Receiver`
   /// e.g. in case of `LIST` or file get commands, servers sends
   /// start data transfer and end data transfer responses.
@@ -419,319 +2033,457 @@ impl FtpTransmitter {
 
   /// Fills the output buffer with the login command (takes `login` string argument ),
   /// modifies `count` variable with the count of written bytes and returns `FtpReceiver`.
-  pub fn send_login(self, buffer: &mut [u8], count: &mut usize, login: &str) -> FtpReceiver {
-    let mut internals = self.internals;
-    let current_state = internals.state.clone();
-
-    match &*internals.state {
-
-      &State::LoginReady => {
-        let data_login = login.as_bytes();
-        let mut my_count = 0;
-        unsafe {
-          ptr::copy_nonoverlapping(&DATA_USER[0], &mut buffer[my_count], DATA_USER.len());
-          my_count += DATA_USER.len();
-          ptr::copy_nonoverlapping(&data_login[0], &mut buffer[my_count], data_login.len());
-          my_count += data_login.len();
-          ptr::copy_nonoverlapping(&DATA_ENDING[0], &mut buffer[my_count], DATA_ENDING.len());
-        };
-        my_count += DATA_ENDING.len();
-        *count = my_count;
-        {
-          let mut int_ref = Rc::get_mut(&mut internals).unwrap();
-          int_ref.state = Rc::new(State::LoginReqSent);
-          int_ref.sent_request = Some(int_ref.state.clone());
-        }
-
-        FtpReceiver { internals: internals }
-      },
-      _ => panic!(format!("send_login is not allowed from the {}" , current_state)),
+  /// Some servers (e.g. anonymous ones) log in without password, i.e. reply `230` at once.
+  pub fn send_login(self, buffer: &mut [u8], count: &mut usize, login: &str) -> Result<FtpReceiver, Self> {
+    match &*self.internals.state {
+      &State::LoginReady => self.send_command_arg(buffer, count, &DATA_USER, login, State::LoginReqSent),
+      _ => Err(self.invalid_state("send_login")),
     }
   }
 
   /// Fills the output buffer with the password command (takes `password` string argument ),
   /// modifies `count` variable with the count of written bytes and returns `FtpReceiver`.
-  pub fn send_password(self, buffer: &mut [u8], count: &mut usize, pass: &str) -> FtpReceiver {
-    let mut internals = self.internals;
-
-    match &*internals.state {
-      &State::PasswordExpected => {
-        let data_password = pass.as_bytes();
-        let mut my_count = 0;
-        unsafe {
-          ptr::copy_nonoverlapping(&DATA_PASS[0], &mut buffer[my_count], DATA_PASS.len());
-          my_count += DATA_PASS.len();
-          ptr::copy_nonoverlapping(&data_password[0], &mut buffer[my_count], data_password.len());
-          my_count += data_password.len();
-          ptr::copy_nonoverlapping(&DATA_ENDING[0], &mut buffer[my_count], DATA_ENDING.len());
-        };
-        my_count += DATA_ENDING.len();
-        *count = my_count;
-        {
-          let mut int_ref = Rc::get_mut(&mut internals).unwrap();
-          int_ref.state = Rc::new(State::PasswordReqSent);
-          int_ref.sent_request = Some(int_ref.state.clone());
-        }
-
-        FtpReceiver { internals: internals }
-      },
-      _ => panic!("send_password is not allowed from the current state"),
+  pub fn send_password(self, buffer: &mut [u8], count: &mut usize, pass: &str) -> Result<FtpReceiver, Self> {
+    match &*self.internals.state {
+      &State::PasswordExpected => self.send_command_arg(buffer, count, &DATA_PASS, pass, State::PasswordReqSent),
+      _ => Err(self.invalid_state("send_password")),
     }
   }
 
   /// Fills the output buffer with the PWD command (take current working directory on remote server),
   /// modifies `count` variable with the count of written bytes and returns `FtpReceiver`.
-  pub fn send_pwd_req(self, buffer: &mut [u8], count: &mut usize) -> FtpReceiver {
-    let mut internals = self.internals;
-
-    match &*internals.state {
-      &State::Authorized => {
-        unsafe { ptr::copy_nonoverlapping(&DATA_PWD[0], &mut buffer[0], DATA_PWD.len()); }
-        *count = DATA_PWD.len();
-        {
-          let mut int_ref = Rc::get_mut(&mut internals).unwrap();
-          int_ref.state = Rc::new(State::PwdReqSent);
-          int_ref.sent_request = Some(int_ref.state.clone());
-        }
-
-        FtpReceiver { internals: internals }
-      },
-      _ => panic!("send_pwd_req is not allowed from the current state"),
+  pub fn send_pwd_req(self, buffer: &mut [u8], count: &mut usize) -> Result<FtpReceiver, Self> {
+    match &*self.internals.state {
+      &State::Authorized => self.send_command(buffer, count, &[&DATA_PWD], State::PwdReqSent),
+      _ => Err(self.invalid_state("send_pwd_req")),
     }
   }
 
-  /// Returns current working directory. Assumes either that  `send_pwd_req` or `send_cwd_req`
+  /// Returns current working directory, if either `send_pwd_req` or `send_cwd_req`
   /// has been sent and succeeded.
-  pub fn get_wd(&self) -> &str {
-    match &self.internals.working_dir {
-      &Some(ref path) => &path,
-      &None           => panic!("get_wd is not available (did you called send_pwd_req?)"),
-    }
+  pub fn get_wd(&self) -> Option<&str> {
+    self.internals.working_dir.as_ref().map(|path| path.as_str())
   }
 
   /// Fills the output buffer with the data transfer mode request (binary or text),
   /// modifies `count` variable with the count of written bytes and returns `FtpReceiver`.
-  pub fn send_type_req(self, buffer: &mut [u8], count: &mut usize, data_type: DataMode) -> FtpReceiver {
-    let mut internals = self.internals;
-
-    match &*internals.state {
+  pub fn send_type_req(self, buffer: &mut [u8], count: &mut usize, data_type: DataMode) -> Result<FtpReceiver, Self> {
+    match &*self.internals.state {
       &State::Authorized => {
-        match &data_type {
-          &DataMode::Binary => {
-            unsafe { ptr::copy_nonoverlapping(&DATA_DATA_BINARY[0], &mut buffer[0], DATA_DATA_BINARY.len()); }
-            *count = DATA_DATA_BINARY.len();
-          },
-          &DataMode::Text => {
-            unsafe { ptr::copy_nonoverlapping(&DATA_DATA_TEXT[0], &mut buffer[0], DATA_DATA_TEXT.len()); }
-            *count = DATA_DATA_TEXT.len();
-          }
+        let data = match &data_type {
+          &DataMode::Binary => *DATA_DATA_BINARY,
+          &DataMode::Text   => *DATA_DATA_TEXT,
         };
-        {
-          let mut int_ref = Rc::get_mut(&mut internals).unwrap();
-          int_ref.state = Rc::new(State::DataTypeReqSent(data_type));
-          int_ref.sent_request = Some(int_ref.state.clone());
-        }
-
-        FtpReceiver { internals: internals }
+        self.send_command(buffer, count, &[data], State::DataTypeReqSent(data_type))
       },
-      _ => panic!("send_type_req is not allowed from the {}", internals.state),
+      _ => Err(self.invalid_state("send_type_req")),
     }
   }
 
-  /// Returns current data mode. Assumes either that  `send_type_req`
-  /// has been sent and succeeded.
-  pub fn get_type(&self) -> &DataMode {
-    match &self.internals.data_mode {
-      &Some(ref mode) => &mode,
-      &None           => panic!("get_type is not available (did you called send_type_req?)"),
-    }
+  /// Returns current data mode, if `send_type_req` has been sent and succeeded.
+  pub fn get_type(&self) -> Option<&DataMode> {
+    self.internals.data_mode.as_ref()
   }
 
   /// Fills the output buffer with the remote system request;
   /// modifies `count` variable with the count of written bytes and returns `FtpReceiver`.
-  pub fn send_system_req(self, buffer: &mut [u8], count: &mut usize) -> FtpReceiver {
-    let mut internals = self.internals;
+  pub fn send_system_req(self, buffer: &mut [u8], count: &mut usize) -> Result<FtpReceiver, Self> {
+    match &*self.internals.state {
+      &State::Authorized => self.send_command(buffer, count, &[&DATA_SYST], State::SystemReqSent),
+      _ => Err(self.invalid_state("send_system_req")),
+    }
+  }
 
-    match &*internals.state {
-      &State::Authorized => {
-        unsafe { ptr::copy_nonoverlapping(&DATA_SYST[0], &mut buffer[0], DATA_SYST.len()); }
-        *count = DATA_SYST.len();
-        {
-          let mut int_ref = Rc::get_mut(&mut internals).unwrap();
-          int_ref.state = Rc::new(State::SystemReqSent);
-          int_ref.sent_request = Some(int_ref.state.clone());
-        }
+  /// Returns remote system with subtype, if `send_system_req` has been sent and succeeded.
+  pub fn get_system(&self) -> Option<(&String, &String)> {
+    match &self.internals.system {
+      &Some((ref name, ref subtype)) => Some((name, subtype)),
+      &None                          => None,
+    }
+  }
 
-        FtpReceiver { internals: internals }
-      },
-      _ => panic!("send_type_req is not allowed from the {}", internals.state),
+  /// Fills the output buffer with the server features request. Once the features are
+  /// known, the commands of not announced extensions (e.g. `send_size_req`) are refused
+  /// with `FtpError::NotSupported`. Modifies `count` variable with the count of written
+  /// bytes and returns `FtpReceiver`.
+  pub fn send_feat_req(self, buffer: &mut [u8], count: &mut usize) -> Result<FtpReceiver, Self> {
+    match &*self.internals.state {
+      &State::Authorized => self.send_command(buffer, count, &[&DATA_FEAT], State::FeatReqSent),
+      _ => Err(self.invalid_state("send_feat_req")),
     }
   }
 
-  /// Returns remote system with subtype. Assumes either that `send_system_req`
-  /// has been sent and succeeded.
-  pub fn get_system(&self) -> (&String, &String) {
-    match &self.internals.system {
-      &Some((ref name, ref subtype)) => (&name, &subtype),
-      &None                          => panic!("get_system is not available (did you called send_system_req?)"),
+  /// Returns server features, if `send_feat_req` has been sent and succeeded.
+  pub fn get_features(&self) -> Option<&Features> {
+    self.internals.features.as_ref()
+  }
+
+  /// Fills the output buffer with `OPTS UTF8 ON` request; on success the session is
+  /// switched to `Encoding::Utf8`. Modifies `count` variable with the count of written
+  /// bytes and returns `FtpReceiver`.
+  pub fn send_opts_utf8_req(self, buffer: &mut [u8], count: &mut usize) -> Result<FtpReceiver, Self> {
+    match &*self.internals.state {
+      &State::Authorized => self.check_feature("UTF8", |features| features.utf8)
+        .and_then(|transmitter| transmitter.send_command(buffer, count, &[&DATA_OPTS_UTF8], State::Utf8ReqSent)),
+      _ => Err(self.invalid_state("send_opts_utf8_req")),
+    }
+  }
+
+  /// Sets up text encoding of the session (`Encoding::Utf8` by default).
+  pub fn set_encoding(&mut self, encoding: Encoding) {
+    Rc::get_mut(&mut self.internals).unwrap().encoding = encoding;
+  }
+
+  /// Returns text encoding of the session.
+  pub fn get_encoding(&self) -> Encoding {
+    self.internals.encoding
+  }
+
+  /// Fills the output buffer with the remote file size request (takes `path` string argument);
+  /// the size depends on the current data mode (see `send_type_req`).
+  /// Modifies `count` variable with the count of written bytes and returns `FtpReceiver`.
+  pub fn send_size_req(self, buffer: &mut [u8], count: &mut usize, path: &str) -> Result<FtpReceiver, Self> {
+    match &*self.internals.state {
+      &State::Authorized => self.check_feature("SIZE", |features| features.size)
+        .and_then(|transmitter| transmitter.send_command_arg(buffer, count, &DATA_SIZE, path, State::SizeReqSent(path.to_string()))),
+      _ => Err(self.invalid_state("send_size_req")),
     }
   }
 
+  /// Returns remote file size, if `send_size_req` has been sent and succeeded.
+  pub fn get_size(&self) -> Option<u64> {
+    self.internals.file_size
+  }
+
+  /// Fills the output buffer with the remote file modification time request (takes `path` string argument),
+  /// modifies `count` variable with the count of written bytes and returns `FtpReceiver`.
+  pub fn send_mdtm_req(self, buffer: &mut [u8], count: &mut usize, path: &str) -> Result<FtpReceiver, Self> {
+    match &*self.internals.state {
+      &State::Authorized => self.check_feature("MDTM", |features| features.mdtm)
+        .and_then(|transmitter| transmitter.send_command_arg(buffer, count, &DATA_MDTM, path, State::MdtmReqSent(path.to_string()))),
+      _ => Err(self.invalid_state("send_mdtm_req")),
+    }
+  }
+
+  /// Returns remote file modification time (UTC), if `send_mdtm_req` has been sent and succeeded.
+  pub fn get_modification_time(&self) -> Option<&Timestamp> {
+    self.internals.modification_time.as_ref()
+  }
+
   /// Fills the output buffer with the PASS requests to allow further data transfer (`LIST` or get file)
   /// modifies `count` variable with the count of written bytes and returns `FtpReceiver`.
-  pub fn send_pasv_req(self, buffer: &mut [u8], count: &mut usize) -> FtpReceiver {
-    let mut internals = self.internals;
+  pub fn send_pasv_req(self, buffer: &mut [u8], count: &mut usize) -> Result<FtpReceiver, Self> {
+    match &*self.internals.state {
+      &State::Authorized => self.send_command(buffer, count, &[&DATA_PASV], State::PassiveReqSent),
+      _ => Err(self.invalid_state("send_pasv_req")),
+    }
+  }
+
+  /// Fills the output buffer with the EPSV requests to allow further data transfer (`LIST` or get file)
+  /// over IPv4 or IPv6. As the server replies with port only, the IP-address of the control connection
+  /// `peer` should be supplied; modifies `count` variable with the count of written bytes and returns `FtpReceiver`.
+  pub fn send_epsv_req(self, buffer: &mut [u8], count: &mut usize, peer: IpAddr) -> Result<FtpReceiver, Self> {
+    match &*self.internals.state {
+      &State::Authorized => self.check_feature("EPSV", |features| features.epsv)
+        .and_then(|transmitter| transmitter.send_command(buffer, count, &[&DATA_EPSV], State::ExtendedPassiveReqSent(peer))),
+      _ => Err(self.invalid_state("send_epsv_req")),
+    }
+  }
 
-    match &*internals.state {
+  /// Fills the output buffer with the PORT request (takes local IPv4 `addr` and `port` arguments),
+  /// i.e. the server will open data connection to the specified endpoint (active mode);
+  /// modifies `count` variable with the count of written bytes and returns `FtpReceiver`.
+  pub fn send_port_req(self, buffer: &mut [u8], count: &mut usize, addr: Ipv4Addr, port: u16) -> Result<FtpReceiver, Self> {
+    match &*self.internals.state {
       &State::Authorized => {
-        unsafe { ptr::copy_nonoverlapping(&DATA_PASV[0], &mut buffer[0], DATA_PASV.len()); }
-        *count = DATA_PASV.len();
-        {
-          let mut int_ref = Rc::get_mut(&mut internals).unwrap();
-          int_ref.state = Rc::new(State::PassiveReqSent);
-          int_ref.sent_request = Some(int_ref.state.clone());
-        }
+        let octets = addr.octets();
+        let endpoint_str = format!("{},{},{},{},{},{}", octets[0], octets[1], octets[2], octets[3], port >> 8, port & 0xFF);
+        self.send_command(buffer, count, &[&DATA_PORT, endpoint_str.as_bytes(), &DATA_ENDING], State::ActiveReqSent)
+      },
+      _ => Err(self.invalid_state("send_port_req")),
+    }
+  }
 
-        FtpReceiver { internals: internals }
+  /// Fills the output buffer with the extended PORT request (takes local IPv4 or IPv6 `addr` argument),
+  /// i.e. the server will open data connection to the specified endpoint (active mode);
+  /// modifies `count` variable with the count of written bytes and returns `FtpReceiver`.
+  pub fn send_eprt_req(self, buffer: &mut [u8], count: &mut usize, addr: SocketAddr) -> Result<FtpReceiver, Self> {
+    match &*self.internals.state {
+      &State::Authorized => {
+        let endpoint_str = match &addr {
+          &SocketAddr::V4(ref v4) => format!("|1|{}|{}|", v4.ip(), v4.port()),
+          &SocketAddr::V6(ref v6) => format!("|2|{}|{}|", v6.ip(), v6.port()),
+        };
+        self.check_feature("EPRT", |features| features.eprt)
+          .and_then(|transmitter| transmitter.send_command(buffer, count, &[&DATA_EPRT, endpoint_str.as_bytes(), &DATA_ENDING], State::ActiveReqSent))
       },
-      _ => panic!("send_pasv_req is not allowed from the {}", internals.state),
+      _ => Err(self.invalid_state("send_eprt_req")),
     }
   }
 
   /// Fills the output buffer with get remove file command (takes `path` string argument ),
   /// modifies `count` variable with the count of written bytes and returns `FtpReceiver`.
-  pub fn send_get_req(self, buffer: &mut [u8], count: &mut usize, file_path: &str) -> FtpReceiver {
-    let mut internals = self.internals;
+  pub fn send_get_req(self, buffer: &mut [u8], count: &mut usize, file_path: &str) -> Result<FtpReceiver, Self> {
+    match &*self.internals.state {
+      &State::Authorized | &State::RestConfirmed(_) => self.send_command_arg(buffer, count, &DATA_RETR, file_path, State::FileReqSent),
+      _ => Err(self.invalid_state("send_get_req")),
+    }
+  }
 
-    match &*internals.state {
-      &State::Authorized => {
-        let data_path = file_path.as_bytes();
-        let mut my_count = 0;
-        unsafe {
-          ptr::copy_nonoverlapping(&DATA_RETR[0], &mut buffer[my_count], DATA_RETR.len());
-          my_count += DATA_RETR.len();
-          ptr::copy_nonoverlapping(&data_path[0], &mut buffer[my_count], data_path.len());
-          my_count += data_path.len();
-          ptr::copy_nonoverlapping(&DATA_ENDING[0], &mut buffer[my_count], DATA_ENDING.len());
-        };
-        my_count += DATA_ENDING.len();
-        {
-          let mut int_ref = Rc::get_mut(&mut internals).unwrap();
-          int_ref.state = Rc::new(State::FileReqSent);
-          int_ref.sent_request = Some(int_ref.state.clone());
-        }
-        *count = my_count;
+  /// Fills the output buffer with put (upload) remote file command (takes `path` string argument ),
+  /// modifies `count` variable with the count of written bytes and returns `FtpReceiver`.
+  pub fn send_put_req(self, buffer: &mut [u8], count: &mut usize, file_path: &str) -> Result<FtpReceiver, Self> {
+    match &*self.internals.state {
+      &State::Authorized | &State::RestConfirmed(_) => self.send_command_arg(buffer, count, &DATA_STOR, file_path, State::FilePutReqSent),
+      _ => Err(self.invalid_state("send_put_req")),
+    }
+  }
+
+  /// Fills the output buffer with append to remote file command (takes `path` string argument ),
+  /// modifies `count` variable with the count of written bytes and returns `FtpReceiver`.
+  pub fn send_append_req(self, buffer: &mut [u8], count: &mut usize, file_path: &str) -> Result<FtpReceiver, Self> {
+    match &*self.internals.state {
+      &State::Authorized | &State::RestConfirmed(_) => self.send_command_arg(buffer, count, &DATA_APPE, file_path, State::FileAppendReqSent),
+      _ => Err(self.invalid_state("send_append_req")),
+    }
+  }
 
-        FtpReceiver { internals: internals }
+  /// Fills the output buffer with restart marker command (takes byte `offset` argument),
+  /// which has to be followed by get, put or append file command.
+  /// Modifies `count` variable with the count of written bytes and returns `FtpReceiver`.
+  pub fn send_rest_req(self, buffer: &mut [u8], count: &mut usize, offset: u64) -> Result<FtpReceiver, Self> {
+    match &*self.internals.state {
+      &State::Authorized => {
+        let offset_str = offset.to_string();
+        self.check_feature("REST STREAM", |features| features.rest_stream)
+          .and_then(|transmitter| transmitter.send_command(buffer, count, &[&DATA_REST, offset_str.as_bytes(), &DATA_ENDING], State::RestReqSent(offset)))
       },
-      _ => panic!("send_get_req is not allowed from the {}", internals.state),
+      _ => Err(self.invalid_state("send_rest_req")),
     }
   }
 
+  /// Returns byte offset, from which the following file transfer starts, if
+  /// `send_rest_req` has been sent and succeeded.
+  pub fn get_rest_offset(&self) -> Option<u64> {
+    self.internals.rest_offset
+  }
+
 
   /// Fills the output buffer with change remote working directory command (takes `path` string argument ),
   /// modifies `count` variable with the count of written bytes and returns `FtpReceiver`.
-  pub fn send_cwd_req(self, buffer: &mut [u8], count: &mut usize, path: &str) -> FtpReceiver {
-    let mut internals = self.internals;
+  pub fn send_cwd_req(self, buffer: &mut [u8], count: &mut usize, path: &str) -> Result<FtpReceiver, Self> {
+    match &*self.internals.state {
+      &State::Authorized => self.send_command_arg(buffer, count, &DATA_CWD, path, State::CwdReqSent(path.to_string())),
+      _ => Err(self.invalid_state("send_cwd_req")),
+    }
+  }
 
-    match &*internals.state {
-      &State::Authorized => {
-        let data_path = path.as_bytes();
-        let mut my_count = 0;
-        unsafe {
-          ptr::copy_nonoverlapping(&DATA_CWD[0], &mut buffer[my_count], DATA_CWD.len());
-          my_count += DATA_CWD.len();
-          ptr::copy_nonoverlapping(&data_path[0], &mut buffer[my_count], path.len());
-          my_count += path.len();
-          ptr::copy_nonoverlapping(&DATA_ENDING[0], &mut buffer[my_count], DATA_ENDING.len());
-        };
-        my_count += DATA_ENDING.len();
-        {
-          let mut int_ref = Rc::get_mut(&mut internals).unwrap();
-          int_ref.state = Rc::new(State::CwdReqSent(path.to_string()));
-          int_ref.sent_request = Some(int_ref.state.clone());
-        }
-        *count = my_count;
+  /// Fills the output buffer with change to parent remote working directory command,
+  /// modifies `count` variable with the count of written bytes and returns `FtpReceiver`.
+  pub fn send_cdup_req(self, buffer: &mut [u8], count: &mut usize) -> Result<FtpReceiver, Self> {
+    match &*self.internals.state {
+      &State::Authorized => self.send_command(buffer, count, &[&DATA_CDUP], State::CdupReqSent),
+      _ => Err(self.invalid_state("send_cdup_req")),
+    }
+  }
 
-        FtpReceiver { internals: internals }
-      },
-      _ => panic!("send_cwd_req is not allowed from the {}", internals.state),
+  /// Fills the output buffer with make remote directory command (takes `path` string argument ),
+  /// modifies `count` variable with the count of written bytes and returns `FtpReceiver`.
+  pub fn send_mkd_req(self, buffer: &mut [u8], count: &mut usize, path: &str) -> Result<FtpReceiver, Self> {
+    match &*self.internals.state {
+      &State::Authorized => self.send_command_arg(buffer, count, &DATA_MKD, path, State::MkdReqSent(path.to_string())),
+      _ => Err(self.invalid_state("send_mkd_req")),
     }
   }
 
+  /// Returns the path of created remote directory, if `send_mkd_req` has been sent and succeeded.
+  pub fn get_created_dir(&self) -> Option<&str> {
+    self.internals.created_dir.as_ref().map(|path| path.as_str())
+  }
 
-  /// Takes pair of IP-address and port, where TCP-connection can be opened to.
-  /// Assumes `send_pasv_req` has been invoked before.
-  pub fn take_endpoint(&mut self) -> (Ipv4Addr, u16) {
-    match Rc::get_mut(&mut self.internals).unwrap().endpoint.take() {
-      Some((addr, port)) => (addr, port),
-      None              => panic!("take_endpoint is not available (did you called send_pass_req?)"),
+  /// Fills the output buffer with remove remote directory command (takes `path` string argument ),
+  /// modifies `count` variable with the count of written bytes and returns `FtpReceiver`.
+  pub fn send_rmd_req(self, buffer: &mut [u8], count: &mut usize, path: &str) -> Result<FtpReceiver, Self> {
+    match &*self.internals.state {
+      &State::Authorized => self.send_command_arg(buffer, count, &DATA_RMD, path, State::RmdReqSent(path.to_string())),
+      _ => Err(self.invalid_state("send_rmd_req")),
     }
   }
 
-  /// Fills the output buffer with `LIST` command to get directory listing of current remote working directory;
+  /// Fills the output buffer with delete remote file command (takes `path` string argument ),
   /// modifies `count` variable with the count of written bytes and returns `FtpReceiver`.
-  pub fn send_list_req(self, buffer: &mut [u8], count: &mut usize) -> FtpReceiver {
-    let mut internals = self.internals;
+  pub fn send_dele_req(self, buffer: &mut [u8], count: &mut usize, path: &str) -> Result<FtpReceiver, Self> {
+    match &*self.internals.state {
+      &State::Authorized => self.send_command_arg(buffer, count, &DATA_DELE, path, State::DeleReqSent(path.to_string())),
+      _ => Err(self.invalid_state("send_dele_req")),
+    }
+  }
 
-    match &*internals.state {
-      &State::Authorized => {
-          unsafe { ptr::copy_nonoverlapping(&DATA_LIST[0], &mut buffer[0], DATA_LIST.len()); }
-          *count = DATA_LIST.len();
-          {
-            let mut int_ref = Rc::get_mut(&mut internals).unwrap();
-            int_ref.state = Rc::new(State::ListReqSent);
-            int_ref.sent_request = Some(int_ref.state.clone());
-          }
-          FtpReceiver { internals: internals }
-        },
-      _ => panic!("send_pass_req is not allowed from the {}", internals.state),
+  /// Fills the output buffer with the first part of rename command, i.e. the remote file
+  /// to be renamed (takes `path` string argument). It has to be followed by `send_rnto_req`.
+  /// Modifies `count` variable with the count of written bytes and returns `FtpReceiver`.
+  pub fn send_rnfr_req(self, buffer: &mut [u8], count: &mut usize, path: &str) -> Result<FtpReceiver, Self> {
+    match &*self.internals.state {
+      &State::Authorized => self.send_command_arg(buffer, count, &DATA_RNFR, path, State::RenameFromReqSent(path.to_string())),
+      _ => Err(self.invalid_state("send_rnfr_req")),
+    }
+  }
+
+  /// Fills the output buffer with the second part of rename command, i.e. the new name
+  /// (takes `path` string argument). Allowed only after successful `send_rnfr_req`.
+  /// Modifies `count` variable with the count of written bytes and returns `FtpReceiver`.
+  pub fn send_rnto_req(self, buffer: &mut [u8], count: &mut usize, path: &str) -> Result<FtpReceiver, Self> {
+    match &*self.internals.state {
+      &State::RenameFromConfirmed(_) => self.send_command_arg(buffer, count, &DATA_RNTO, path, State::RenameToReqSent(path.to_string())),
+      _ => Err(self.invalid_state("send_rnto_req")),
     }
   }
 
-  /// Parses remote directory listing, requested by `send_list_req` command.
-  pub fn parse_list(&self, data: &[u8]) -> Result<Vec<RemoteFile>, FtpError> {
 
-    lazy_static! {
-      static ref RE_LINE: Regex = Regex::new("(?m:^(.+)\r$)").unwrap();
-      static ref RE_FILE: Regex = Regex::new("^([d-])(?:[rwx-]{3}){3} +\\d+ +\\w+ +\\w+ +(\\d+) +(.+) +(.+)$").unwrap();
-    }
-    str::from_utf8(data)
-      .map_err(|_| FtpError::GarbageData)
-      .and_then(|list|{
-        let line_captures = RE_LINE.captures_iter(list);
-        let files = line_captures
-          .filter_map(|line_cap| {
-            let line = line_cap.at(1).unwrap();
-            // println!("line = {}", line);
-            match RE_FILE.captures(line) {
-              None => None,
-              Some(captures) => {
-                let kind_str = captures.at(1).unwrap();
-                let size_str = captures.at(2).unwrap();
-                let name = captures.at(4).unwrap();
-                let kind = match kind_str {
-                  "d" => RemoteFileKind::Directory,
-                  "-" => RemoteFileKind::File,
-                  _   => unreachable!(),
-                };
-                let size:usize = size_str.parse().unwrap();
-                // println!("remote file: {} ({})", name, size);
-                let remote_file = RemoteFile {
-                  kind: kind,
-                  size: size,
-                  name: name.to_string(),
-                };
-                Some(remote_file)
-              }
-            }
-          });
-        let mut vec:Vec<RemoteFile> = Vec::new();
-        for file in files {
-          vec.push(file);
-        }
-        Ok(vec)
+  /// Takes socket address (IP-address and port), where TCP-connection can be opened to,
+  /// if `send_pasv_req` or `send_epsv_req` has been invoked before.
+  pub fn take_endpoint(&mut self) -> Option<SocketAddr> {
+    Rc::get_mut(&mut self.internals).unwrap().endpoint.take()
+  }
+
+  /// Fills the output buffer with `LIST` command to get directory listing of the `argument` path
+  /// (or of current remote working directory); the `argument` might be options as well, e.g. `-l`,
+  /// if the server supports them. Modifies `count` variable with the count of written bytes and
+  /// returns `FtpReceiver`.
+  pub fn send_list_req(self, buffer: &mut [u8], count: &mut usize, argument: Option<&str>) -> Result<FtpReceiver, Self> {
+    match &*self.internals.state {
+      &State::Authorized => self.send_command_opt_arg(buffer, count, &DATA_LIST, argument, State::ListReqSent),
+      _ => Err(self.invalid_state("send_list_req")),
+    }
+  }
+
+  /// Fills the output buffer with `NLST` command to get names only listing of the `argument` path
+  /// (or of current remote working directory). Modifies `count` variable with the count of written
+  /// bytes and returns `FtpReceiver`.
+  pub fn send_nlst_req(self, buffer: &mut [u8], count: &mut usize, argument: Option<&str>) -> Result<FtpReceiver, Self> {
+    match &*self.internals.state {
+      &State::Authorized => self.send_command_opt_arg(buffer, count, &DATA_NLST, argument, State::NlstReqSent),
+      _ => Err(self.invalid_state("send_nlst_req")),
+    }
+  }
+
+  /// Parses names only listing, requested by `send_nlst_req` command.
+  pub fn parse_nlst(&self, data: &[u8]) -> Vec<RemoteName> {
+    let encoding = self.internals.encoding;
+    data.split(|&b| b == b'\n')
+      .map(|line| encoding.decode_escaped(line))
+      .filter(|line| !line.trim_right_matches('\r').is_empty())
+      .map(|line|{
+        let name = line.trim_right_matches('\r');
+        RemoteName { name: encoding.unescaped(name), raw_name: encoding.escaped_bytes(name) }
       })
+      .collect()
+  }
+
+  /// Fills the output buffer with `MLSD` command to get machine-readable listing of the
+  /// directory `path` (or of the current remote working directory). Modifies `count`
+  /// variable with the count of written bytes and returns `FtpReceiver`.
+  pub fn send_mlsd_req(self, buffer: &mut [u8], count: &mut usize, path: Option<&str>) -> Result<FtpReceiver, Self> {
+    match &*self.internals.state {
+      &State::Authorized => self.check_feature("MLST", |features| features.mlst.is_some())
+        .and_then(|transmitter| transmitter.send_command_opt_arg(buffer, count, &DATA_MLSD, path, State::MlsdReqSent)),
+      _ => Err(self.invalid_state("send_mlsd_req")),
+    }
+  }
+
+  /// Parses machine-readable directory listing, requested by `send_mlsd_req` command;
+  /// malformed lines are reported as errors in place of the files.
+  pub fn parse_mlsd(&self, data: &[u8]) -> Vec<Result<RemoteFile, FtpError>> {
+    let encoding = self.internals.encoding;
+    data.split(|&b| b == b'\n')
+      .map(|line| encoding.decode_escaped(line))
+      .filter(|line| !line.trim_right_matches('\r').is_empty())
+      .map(|line| RemoteFile::parse_facts(line.trim_right_matches('\r')).map(|file| file.unescaped(&encoding)))
+      .collect()
+  }
+
+  /// Fills the output buffer with `MLST` command to get the facts of the single file `path`
+  /// (or of the current remote working directory) via control connection. Modifies `count`
+  /// variable with the count of written bytes and returns `FtpReceiver`.
+  pub fn send_mlst_req(self, buffer: &mut [u8], count: &mut usize, path: Option<&str>) -> Result<FtpReceiver, Self> {
+    match &*self.internals.state {
+      &State::Authorized => {
+        let sent_request = State::MlstReqSent(path.unwrap_or("").to_string());
+        self.check_feature("MLST", |features| features.mlst.is_some())
+          .and_then(|transmitter| transmitter.send_command_opt_arg(buffer, count, &DATA_MLST, path, sent_request))
+      },
+      _ => Err(self.invalid_state("send_mlst_req")),
+    }
+  }
+
+  /// Returns file facts, if `send_mlst_req` has been sent and succeeded.
+  pub fn get_file_facts(&self) -> Option<&RemoteFile> {
+    self.internals.file_facts.as_ref()
+  }
+
+  /// Parses remote directory listing, requested by `send_list_req` command, into entry per line.
+  /// The listing format is detected per line (see `ListParser`); DOS format is tried first,
+  /// if the server has reported `Windows_NT` system (see `send_system_req`). Headers and
+  /// empty lines are skipped, other lines, which can't be parsed, are reported as
+  /// `FtpError::UnparseableLine` entries, without discarding the rest of listing.
+  /// The dates without year are resolved against the current time.
+  pub fn parse_list(&self, data: &[u8]) -> Vec<Result<RemoteFile, FtpError>> {
+    self.parse_list_at(data, &Timestamp::from_system_time(SystemTime::now()))
+  }
+
+  /// The same as `parse_list`, but the dates without year are resolved against `now`.
+  pub fn parse_list_at(&self, data: &[u8], now: &Timestamp) -> Vec<Result<RemoteFile, FtpError>> {
+    let mut stream = self.list_stream_at(now);
+    stream.feed(data);
+    stream.finish();
+    let mut list = Vec::new();
+    while let Some(file) = stream.next_file() {
+      list.push(file);
+    }
+    list
+  }
+
+  /// Creates incremental parser of remote directory listing, e.g. for huge directories;
+  /// the lines are parsed in the same way as by `parse_list`.
+  pub fn list_stream(&self) -> ListStream {
+    self.list_stream_at(&Timestamp::from_system_time(SystemTime::now()))
+  }
+
+  /// The same as `list_stream`, but the dates without year are resolved against `now`.
+  pub fn list_stream_at(&self, now: &Timestamp) -> ListStream {
+    ListStream {
+      parsers: self.list_parsers(),
+      encoding: self.internals.encoding,
+      now: *now,
+      pending: Vec::new(),
+      consumed: 0,
+      finished: false,
+    }
+  }
+
+  /// Adds custom listing parser, which is tried before the built-in ones.
+  pub fn add_list_parser(&mut self, parser: Box<dyn ListParser>) {
+    Rc::get_mut(&mut self.internals).unwrap().list_parsers.push(Rc::from(parser));
+  }
+
+  /// Returns custom listing parsers followed by the built-in ones in the order of preference.
+  fn list_parsers(&self) -> Vec<Rc<dyn ListParser>> {
+    let windows = match self.get_system() {
+      Some((name, _)) => name == "Windows_NT",
+      None            => false,
+    };
+    let mut parsers = self.internals.list_parsers.clone();
+    if windows {
+      parsers.push(Rc::new(DosListParser));
+      parsers.push(Rc::new(UnixListParser));
+    } else {
+      parsers.push(Rc::new(UnixListParser));
+      parsers.push(Rc::new(DosListParser));
+    }
+    parsers.push(Rc::new(EplfListParser));
+    parsers.push(Rc::new(VmsListParser));
+    parsers.push(Rc::new(MvsListParser));
+    parsers
   }
 
 }
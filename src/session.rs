@@ -0,0 +1,317 @@
+//! Optional typed layer over `FtpReceiver` and `FtpTransmitter`: the session state
+//! (connected, logged in, transferring data etc.) is the type parameter of `Session`,
+//! so only the commands, allowed in the state, are available at compile time.
+//!
+//! Every command returns `Pending` session, which is fed by the server reply; the
+//! `Outcome` is the session in the next state, or in the previous one, if the command
+//! has been rejected by the server.
+//!
+//! ```compile_fail
+//! use protocol_ftp_client::session::*;
+//!
+//! fn list_before_login(session: Session<Connected>, buffer: &mut [u8], count: &mut usize) {
+//!   let _ = session.send_list_req(buffer, count, None);
+//! }
+//! ```
+
+use std::marker::PhantomData;
+use std::net::{IpAddr, Ipv4Addr, SocketAddr};
+
+use super::{DataMode, FtpError, FtpReceiver, FtpTransmitter, State};
+
+/// The connection is closed (or should be closed), e.g. the greeting is rejected.
+pub struct Closed;
+/// The greeting has been received, the login is expected.
+pub struct Connected;
+/// The login has been accepted, the password is expected.
+pub struct AwaitingPassword;
+/// Any command can be sent.
+pub struct LoggedIn;
+/// The restart offset has been accepted, the data transfer command is expected.
+pub struct Restarting;
+/// The source file of renaming has been accepted, the destination is expected.
+pub struct Renaming;
+/// The data transfer is in progress via data connection.
+pub struct Transferring;
+
+/// FTP session in the `S` state, ready to send commands.
+pub struct Session<S> {
+  transmitter: FtpTransmitter,
+  state: PhantomData<S>,
+}
+
+/// The command has been sent, and the server reply is expected; `S` is the state of
+/// session after successful reply, and `F` is the state after the rejected command.
+pub struct Pending<S, F> {
+  receiver: FtpReceiver,
+  state: PhantomData<(S, F)>,
+}
+
+/// The result of feeding `Pending` session with the server reply.
+pub enum Outcome<S, F> {
+  /// The command succeeded.
+  Done(Session<S>),
+  /// Not enough data has been fed yet.
+  Pending(Pending<S, F>),
+  /// The command has been rejected by the server (i.e. `FtpError::ServerError`
+  /// or `FtpError::AuthFailed`).
+  Rejected(Session<F>, FtpError),
+  /// The reply is not valid, the session can't be used further.
+  Error(FtpError),
+}
+
+/// Starts new session, which expects the server greeting.
+pub fn connect() -> Pending<Connected, Closed> {
+  Pending::wrap(FtpReceiver::new())
+}
+
+impl<S, F> Pending<S, F> {
+
+  fn wrap(receiver: FtpReceiver) -> Self {
+    Pending { receiver: receiver, state: PhantomData }
+  }
+
+  /// Feeds newly received bytes (see `FtpReceiver::try_advance`).
+  pub fn feed(self, buffer: &[u8]) -> Outcome<S, F> {
+    match self.receiver.try_advance(buffer) {
+      Ok(transmitter) => Outcome::Done(Session::wrap(transmitter)),
+      Err(mut receiver) => match receiver.take_error() {
+        None | Some(FtpError::NotEnoughData) => Outcome::Pending(Pending::wrap(receiver)),
+        Some(error @ FtpError::ServerError(_, _)) | Some(error @ FtpError::AuthFailed) => {
+          Outcome::Rejected(Session::wrap(receiver.to_transmitter()), error)
+        },
+        Some(error) => Outcome::Error(error),
+      },
+    }
+  }
+
+  /// Returns the underlying receiver.
+  pub fn receiver(&self) -> &FtpReceiver {
+    &self.receiver
+  }
+}
+
+impl<S> Session<S> {
+
+  fn wrap(transmitter: FtpTransmitter) -> Self {
+    Session { transmitter: transmitter, state: PhantomData }
+  }
+
+  /// Sends the command via underlying transmitter; on failure the session is returned
+  /// unmodified, and the error can be obtained via `take_error`.
+  fn send<N, F, C>(self, command: C) -> Result<Pending<N, F>, Self>
+    where C: FnOnce(FtpTransmitter) -> Result<FtpReceiver, FtpTransmitter> {
+    match command(self.transmitter) {
+      Ok(receiver)     => Ok(Pending::wrap(receiver)),
+      Err(transmitter) => Err(Session::wrap(transmitter)),
+    }
+  }
+
+  /// Returns the underlying transmitter, e.g. to get the results of commands (`get_wd`,
+  /// `get_size` etc.) or to parse directory listings.
+  pub fn transmitter(&self) -> &FtpTransmitter {
+    &self.transmitter
+  }
+
+  /// Returns the underlying transmitter, e.g. to `take_endpoint` or to `set_encoding`.
+  pub fn transmitter_mut(&mut self) -> &mut FtpTransmitter {
+    &mut self.transmitter
+  }
+
+  /// Returns the last occurred error, and internally sets up `None`.
+  pub fn take_error(&mut self) -> Option<FtpError> {
+    self.transmitter.take_error()
+  }
+
+  /// Leaves the typed layer.
+  pub fn into_transmitter(self) -> FtpTransmitter {
+    self.transmitter
+  }
+}
+
+impl Session<Connected> {
+
+  /// Sends `USER` (see `FtpTransmitter::send_login`); the session awaits the password then.
+  pub fn send_login(self, buffer: &mut [u8], count: &mut usize, login: &str) -> Result<Pending<AwaitingPassword, Connected>, Self> {
+    self.send(|transmitter| transmitter.send_login(buffer, count, login))
+  }
+}
+
+impl Session<AwaitingPassword> {
+
+  /// Returns logged in session, if the server has not asked for the password (e.g. for
+  /// anonymous login); otherwise returns the session unmodified.
+  pub fn logged_in(self) -> Result<Session<LoggedIn>, Self> {
+    match &*self.transmitter.internals.state {
+      &State::Authorized => Ok(Session::wrap(self.transmitter)),
+      _ => Err(self),
+    }
+  }
+
+  /// Sends `PASS`; the session returns to `Connected` state, if the credentials are rejected.
+  pub fn send_password(self, buffer: &mut [u8], count: &mut usize, pass: &str) -> Result<Pending<LoggedIn, Connected>, Self> {
+    self.send(|transmitter| transmitter.send_password(buffer, count, pass))
+  }
+}
+
+impl Session<LoggedIn> {
+
+  /// Sends `PWD`; the directory is available via `transmitter().get_wd()` afterwards.
+  pub fn send_pwd_req(self, buffer: &mut [u8], count: &mut usize) -> Result<Pending<LoggedIn, LoggedIn>, Self> {
+    self.send(|transmitter| transmitter.send_pwd_req(buffer, count))
+  }
+
+  /// Changes remote working directory to `path`.
+  pub fn send_cwd_req(self, buffer: &mut [u8], count: &mut usize, path: &str) -> Result<Pending<LoggedIn, LoggedIn>, Self> {
+    self.send(|transmitter| transmitter.send_cwd_req(buffer, count, path))
+  }
+
+  /// Changes remote working directory to the parent one.
+  pub fn send_cdup_req(self, buffer: &mut [u8], count: &mut usize) -> Result<Pending<LoggedIn, LoggedIn>, Self> {
+    self.send(|transmitter| transmitter.send_cdup_req(buffer, count))
+  }
+
+  /// Creates remote directory; see `FtpTransmitter::get_created_dir`.
+  pub fn send_mkd_req(self, buffer: &mut [u8], count: &mut usize, path: &str) -> Result<Pending<LoggedIn, LoggedIn>, Self> {
+    self.send(|transmitter| transmitter.send_mkd_req(buffer, count, path))
+  }
+
+  /// Removes remote directory.
+  pub fn send_rmd_req(self, buffer: &mut [u8], count: &mut usize, path: &str) -> Result<Pending<LoggedIn, LoggedIn>, Self> {
+    self.send(|transmitter| transmitter.send_rmd_req(buffer, count, path))
+  }
+
+  /// Deletes remote file.
+  pub fn send_dele_req(self, buffer: &mut [u8], count: &mut usize, path: &str) -> Result<Pending<LoggedIn, LoggedIn>, Self> {
+    self.send(|transmitter| transmitter.send_dele_req(buffer, count, path))
+  }
+
+  /// Starts renaming of `path`: only `send_rnto_req` is allowed in the `Renaming` state.
+  pub fn send_rnfr_req(self, buffer: &mut [u8], count: &mut usize, path: &str) -> Result<Pending<Renaming, LoggedIn>, Self> {
+    self.send(|transmitter| transmitter.send_rnfr_req(buffer, count, path))
+  }
+
+  /// Sets up data transfer mode (`TYPE`).
+  pub fn send_type_req(self, buffer: &mut [u8], count: &mut usize, data_type: DataMode) -> Result<Pending<LoggedIn, LoggedIn>, Self> {
+    self.send(|transmitter| transmitter.send_type_req(buffer, count, data_type))
+  }
+
+  /// Sends `SYST`; the system is available via `transmitter().get_system()` afterwards.
+  pub fn send_system_req(self, buffer: &mut [u8], count: &mut usize) -> Result<Pending<LoggedIn, LoggedIn>, Self> {
+    self.send(|transmitter| transmitter.send_system_req(buffer, count))
+  }
+
+  /// Sends `FEAT`; the features are available via `transmitter().get_features()` afterwards.
+  pub fn send_feat_req(self, buffer: &mut [u8], count: &mut usize) -> Result<Pending<LoggedIn, LoggedIn>, Self> {
+    self.send(|transmitter| transmitter.send_feat_req(buffer, count))
+  }
+
+  /// Switches the session into UTF-8 encoding (`OPTS UTF8 ON`).
+  pub fn send_opts_utf8_req(self, buffer: &mut [u8], count: &mut usize) -> Result<Pending<LoggedIn, LoggedIn>, Self> {
+    self.send(|transmitter| transmitter.send_opts_utf8_req(buffer, count))
+  }
+
+  /// Requests the size of remote file; see `FtpTransmitter::get_size`.
+  pub fn send_size_req(self, buffer: &mut [u8], count: &mut usize, path: &str) -> Result<Pending<LoggedIn, LoggedIn>, Self> {
+    self.send(|transmitter| transmitter.send_size_req(buffer, count, path))
+  }
+
+  /// Requests the modification time of remote file; see `FtpTransmitter::get_modification_time`.
+  pub fn send_mdtm_req(self, buffer: &mut [u8], count: &mut usize, path: &str) -> Result<Pending<LoggedIn, LoggedIn>, Self> {
+    self.send(|transmitter| transmitter.send_mdtm_req(buffer, count, path))
+  }
+
+  /// Requests the facts of remote file; see `FtpTransmitter::get_file_facts`.
+  pub fn send_mlst_req(self, buffer: &mut [u8], count: &mut usize, path: Option<&str>) -> Result<Pending<LoggedIn, LoggedIn>, Self> {
+    self.send(|transmitter| transmitter.send_mlst_req(buffer, count, path))
+  }
+
+  /// Requests passive data connection; the endpoint is taken via `transmitter_mut().take_endpoint()`.
+  pub fn send_pasv_req(self, buffer: &mut [u8], count: &mut usize) -> Result<Pending<LoggedIn, LoggedIn>, Self> {
+    self.send(|transmitter| transmitter.send_pasv_req(buffer, count))
+  }
+
+  /// Requests extended passive data connection to the `peer` (i.e. the server) address.
+  pub fn send_epsv_req(self, buffer: &mut [u8], count: &mut usize, peer: IpAddr) -> Result<Pending<LoggedIn, LoggedIn>, Self> {
+    self.send(|transmitter| transmitter.send_epsv_req(buffer, count, peer))
+  }
+
+  /// Requests active data connection to IPv4 `addr` and `port` of the client.
+  pub fn send_port_req(self, buffer: &mut [u8], count: &mut usize, addr: Ipv4Addr, port: u16) -> Result<Pending<LoggedIn, LoggedIn>, Self> {
+    self.send(|transmitter| transmitter.send_port_req(buffer, count, addr, port))
+  }
+
+  /// Requests active data connection to `addr` of the client (`EPRT`, IPv4 or IPv6).
+  pub fn send_eprt_req(self, buffer: &mut [u8], count: &mut usize, addr: SocketAddr) -> Result<Pending<LoggedIn, LoggedIn>, Self> {
+    self.send(|transmitter| transmitter.send_eprt_req(buffer, count, addr))
+  }
+
+  /// Sets up restart offset: only file transfer commands are allowed in the `Restarting` state.
+  pub fn send_rest_req(self, buffer: &mut [u8], count: &mut usize, offset: u64) -> Result<Pending<Restarting, LoggedIn>, Self> {
+    self.send(|transmitter| transmitter.send_rest_req(buffer, count, offset))
+  }
+
+  /// Requests directory listing via data connection; call `finish` as soon as it is closed.
+  pub fn send_list_req(self, buffer: &mut [u8], count: &mut usize, argument: Option<&str>) -> Result<Pending<Transferring, LoggedIn>, Self> {
+    self.send(|transmitter| transmitter.send_list_req(buffer, count, argument))
+  }
+
+  /// Requests the list of names via data connection; call `finish` as soon as it is closed.
+  pub fn send_nlst_req(self, buffer: &mut [u8], count: &mut usize, argument: Option<&str>) -> Result<Pending<Transferring, LoggedIn>, Self> {
+    self.send(|transmitter| transmitter.send_nlst_req(buffer, count, argument))
+  }
+
+  /// Requests machine-readable listing via data connection; call `finish` as soon as it is closed.
+  pub fn send_mlsd_req(self, buffer: &mut [u8], count: &mut usize, path: Option<&str>) -> Result<Pending<Transferring, LoggedIn>, Self> {
+    self.send(|transmitter| transmitter.send_mlsd_req(buffer, count, path))
+  }
+
+  /// Downloads remote file via data connection; call `finish` as soon as it is closed.
+  pub fn send_get_req(self, buffer: &mut [u8], count: &mut usize, file_path: &str) -> Result<Pending<Transferring, LoggedIn>, Self> {
+    self.send(|transmitter| transmitter.send_get_req(buffer, count, file_path))
+  }
+
+  /// Uploads file via data connection; call `finish` as soon as it is closed.
+  pub fn send_put_req(self, buffer: &mut [u8], count: &mut usize, file_path: &str) -> Result<Pending<Transferring, LoggedIn>, Self> {
+    self.send(|transmitter| transmitter.send_put_req(buffer, count, file_path))
+  }
+
+  /// Appends data to remote file via data connection; call `finish` as soon as it is closed.
+  pub fn send_append_req(self, buffer: &mut [u8], count: &mut usize, file_path: &str) -> Result<Pending<Transferring, LoggedIn>, Self> {
+    self.send(|transmitter| transmitter.send_append_req(buffer, count, file_path))
+  }
+}
+
+impl Session<Restarting> {
+
+  /// Downloads remote file starting from the restart offset.
+  pub fn send_get_req(self, buffer: &mut [u8], count: &mut usize, file_path: &str) -> Result<Pending<Transferring, LoggedIn>, Self> {
+    self.send(|transmitter| transmitter.send_get_req(buffer, count, file_path))
+  }
+
+  /// Uploads file starting from the restart offset.
+  pub fn send_put_req(self, buffer: &mut [u8], count: &mut usize, file_path: &str) -> Result<Pending<Transferring, LoggedIn>, Self> {
+    self.send(|transmitter| transmitter.send_put_req(buffer, count, file_path))
+  }
+
+  /// Appends data to remote file starting from the restart offset.
+  pub fn send_append_req(self, buffer: &mut [u8], count: &mut usize, file_path: &str) -> Result<Pending<Transferring, LoggedIn>, Self> {
+    self.send(|transmitter| transmitter.send_append_req(buffer, count, file_path))
+  }
+}
+
+impl Session<Renaming> {
+
+  /// Completes renaming: the file, given to `send_rnfr_req`, gets the new `path`.
+  pub fn send_rnto_req(self, buffer: &mut [u8], count: &mut usize, path: &str) -> Result<Pending<LoggedIn, LoggedIn>, Self> {
+    self.send(|transmitter| transmitter.send_rnto_req(buffer, count, path))
+  }
+}
+
+impl Session<Transferring> {
+
+  /// Expects the end of data transfer reply, i.e. after data connection has been closed.
+  pub fn finish(self) -> Pending<LoggedIn, LoggedIn> {
+    Pending::wrap(self.transmitter.to_receiver())
+  }
+}
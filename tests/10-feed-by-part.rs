@@ -37,3 +37,45 @@ fn cycled_advance() {
 
   assert_eq!(trans_opt.is_some(), true);
 }
+
+#[test]
+fn replies_in_one_chunk() {
+  let mut tx_buff:[u8; 1024] = [0; 1024];
+  let mut tx_count = 0;
+
+  let chunk = "150 Opening BINARY mode data connection for favicon.ico (5430 bytes).\r\n226 Transfer complete.\r\n";
+  let ftp_transmitter = FtpReceiver::new()
+    .try_advance("220 Service ready for new user.\r\n331 Please specify".as_bytes()).ok().unwrap()
+    .send_login(&mut tx_buff, &mut tx_count, "anonymous").ok().unwrap()
+    .try_advance(" the password.\r\n".as_bytes()).ok().unwrap()
+    .send_password(&mut tx_buff, &mut tx_count, "anonymous@nowhere.com").ok().unwrap()
+    .try_advance("230 Login successful.\r\n".as_bytes()).ok().unwrap()
+    .send_get_req(&mut tx_buff, &mut tx_count, "favicon.ico").ok().unwrap()
+    .try_advance(chunk.as_bytes()).ok().unwrap();
+  assert_eq!(ftp_transmitter.get_last_reply().unwrap().code, 150);
+
+  let ftp_transmitter = ftp_transmitter.to_receiver().try_advance(&[]).ok().unwrap();
+  assert_eq!(ftp_transmitter.get_last_reply().unwrap().code, 226);
+
+  let mut ftp_reciver = ftp_transmitter.send_pwd_req(&mut tx_buff, &mut tx_count).ok().unwrap()
+    .try_advance(&[]).err().unwrap();
+  assert_eq!(ftp_reciver.take_error().unwrap(), FtpError::NotEnoughData);
+}
+
+#[test]
+fn split_utf8_sequence() {
+  let mut tx_buff:[u8; 1024] = [0; 1024];
+  let mut tx_count = 0;
+
+  let reply = "257 \"/pub/тест\" is the current directory\r\n".as_bytes();
+  let ftp_reciver = FtpReceiver::new()
+    .try_advance("220 Service ready for new user.\r\n".as_bytes()).ok().unwrap()
+    .send_login(&mut tx_buff, &mut tx_count, "anonymous").ok().unwrap()
+    .try_advance("331 Please specify the password.\r\n".as_bytes()).ok().unwrap()
+    .send_password(&mut tx_buff, &mut tx_count, "anonymous@nowhere.com").ok().unwrap()
+    .try_advance("230 Login successful.\r\n".as_bytes()).ok().unwrap()
+    .send_pwd_req(&mut tx_buff, &mut tx_count).ok().unwrap()
+    .try_advance(&reply[0 .. 11]).err().unwrap();
+  let ftp_transmitter = ftp_reciver.try_advance(&reply[11 ..]).ok().unwrap();
+  assert_eq!(ftp_transmitter.get_wd(), Some("/pub/тест"));
+}
@@ -0,0 +1,91 @@
+extern crate protocol_ftp_client;
+
+use protocol_ftp_client::*;
+
+#[test]
+fn banner_text() {
+  let mut tx_buff:[u8; 1024] = [0; 1024];
+  let mut tx_count = 0;
+
+  let banner = "230-\r
+230-This is ftp0.ydx.FreeBSD.org, graciously hosted by Yandex.\r
+230-\r
+230-FreeBSD files can be found in the /pub/FreeBSD directory.\r
+230-\r
+230 Login successful.\r
+";
+  let ftp_transmitter = FtpReceiver::new()
+    .try_advance("220 This is ftp0.ydx.freebsd.org - hosted at Yandex.\r\n".as_bytes()).ok().unwrap()
+    .send_login(&mut tx_buff, &mut tx_count, "anonymous").ok().unwrap()
+    .try_advance("331 Please specify the password.\r\n".as_bytes()).ok().unwrap()
+    .send_password(&mut tx_buff, &mut tx_count, "anonymous@nowhere.com").ok().unwrap()
+    .try_advance(banner.as_bytes()).ok().unwrap();
+
+  assert_eq!(ftp_transmitter.get_last_reply().unwrap(), &Reply {
+    code: 230,
+    lines: vec![
+      "".to_string(),
+      "This is ftp0.ydx.FreeBSD.org, graciously hosted by Yandex.".to_string(),
+      "".to_string(),
+      "FreeBSD files can be found in the /pub/FreeBSD directory.".to_string(),
+      "".to_string(),
+      "Login successful.".to_string(),
+    ]
+  });
+}
+
+#[test]
+fn continuation_with_digits() {
+  let greeting = "220-Welcome\r\n230 users are online\r\n  220 is not the end\r\n220-still going\r\n220 Ready\r\n";
+
+  let ftp_reciver = FtpReceiver::new()
+    .try_advance(&greeting.as_bytes()[0 .. 34]).err().unwrap();
+  assert_eq!(ftp_reciver.get_last_reply(), None);
+
+  let ftp_transmitter = ftp_reciver.try_advance(&greeting.as_bytes()[34 ..]).ok().unwrap();
+  assert_eq!(ftp_transmitter.get_last_reply().unwrap(), &Reply {
+    code: 220,
+    lines: vec![
+      "Welcome".to_string(),
+      "230 users are online".to_string(),
+      "  220 is not the end".to_string(),
+      "still going".to_string(),
+      "Ready".to_string(),
+    ]
+  });
+}
+
+#[test]
+fn error_reply_text() {
+  let mut ftp_reciver = FtpReceiver::new()
+    .try_advance("421-Too many connections\r\n421 Try again later\n".as_bytes()).err().unwrap();
+  assert_eq!(ftp_reciver.get_last_reply().unwrap().code, 421);
+  assert_eq!(ftp_reciver.take_error().unwrap(),
+             FtpError::ServerError(421, "Too many connections\nTry again later".to_string()));
+}
+
+#[test]
+fn garbage() {
+  let mut ftp_reciver = FtpReceiver::new().try_advance("Hello".as_bytes()).err().unwrap();
+  assert_eq!(ftp_reciver.take_error().unwrap(), FtpError::GarbageData);
+
+  ftp_reciver = ftp_reciver.try_advance("22".as_bytes()).err().unwrap();
+  assert_eq!(ftp_reciver.take_error().unwrap(), FtpError::NotEnoughData);
+}
+
+#[test]
+fn non_ascii_digits() {
+  let mut ftp_reciver = FtpReceiver::new().try_advance("٢٢٠ hi\r\n".as_bytes()).err().unwrap();
+  assert_eq!(ftp_reciver.take_error().unwrap(), FtpError::GarbageData);
+}
+
+#[test]
+fn leading_zero_code() {
+  let mut ftp_reciver = FtpReceiver::new()
+    .try_advance("099-x\r\n99é\r\n099 end\r\n".as_bytes()).err().unwrap();
+  assert_eq!(ftp_reciver.get_last_reply().unwrap(), &Reply {
+    code: 99,
+    lines: vec!["x".to_string(), "99é".to_string(), "end".to_string()]
+  });
+  assert_eq!(ftp_reciver.take_error().unwrap(), FtpError::ServerError(99, "x\n99é\nend".to_string()));
+}
@@ -1,8 +1,11 @@
 extern crate protocol_ftp_client;
 
+mod common;
+
 use protocol_ftp_client::*;
+use common::files;
 use std::str;
-use std::net::Ipv4Addr;
+use std::net::{Ipv4Addr, SocketAddr, SocketAddrV4};
 
 #[test]
 fn session_sample() {
@@ -13,12 +16,12 @@ fn session_sample() {
 
   ftp_reciver = ftp_reciver
     .try_advance("220 This is ftp0.ydx.freebsd.org - hosted at Yandex.\r\n".as_bytes()).ok().unwrap()
-    .send_login(&mut tx_buff, &mut tx_count, "anonymous");
+    .send_login(&mut tx_buff, &mut tx_count, "anonymous").ok().unwrap();
   assert_eq!(str::from_utf8(&tx_buff[0 .. tx_count]).unwrap(), "USER anonymous\r\n");
 
   ftp_reciver = ftp_reciver
     .try_advance("331 Please specify the password.\r\n".as_bytes()).ok().unwrap()
-    .send_password(&mut tx_buff, &mut tx_count, "anonymous@nowhere.com");
+    .send_password(&mut tx_buff, &mut tx_count, "anonymous@nowhere.com").ok().unwrap();
   assert_eq!(str::from_utf8(&tx_buff[0 .. tx_count]).unwrap(), "PASS anonymous@nowhere.com\r\n");
 
   let banner = "230-\r
@@ -30,33 +33,33 @@ fn session_sample() {
 ";
   let mut ftp_transmitter = ftp_reciver.try_advance(banner.as_bytes()).ok().unwrap();
 
-  ftp_reciver = ftp_transmitter.send_type_req(&mut tx_buff, &mut tx_count, DataMode::Binary);
+  ftp_reciver = ftp_transmitter.send_type_req(&mut tx_buff, &mut tx_count, DataMode::Binary).ok().unwrap();
   assert_eq!(str::from_utf8(&tx_buff[0 .. tx_count]).unwrap(), "TYPE I\r\n");
   ftp_transmitter = ftp_reciver.try_advance("200 Switching to Binary mode.\r\n".as_bytes()).ok().unwrap();
-  assert_eq!(ftp_transmitter.get_type(), &DataMode::Binary);
+  assert_eq!(ftp_transmitter.get_type(), Some(&DataMode::Binary));
 
-  ftp_reciver = ftp_transmitter.send_system_req(&mut tx_buff, &mut tx_count);
+  ftp_reciver = ftp_transmitter.send_system_req(&mut tx_buff, &mut tx_count).ok().unwrap();
   assert_eq!(str::from_utf8(&tx_buff[0 .. tx_count]).unwrap(), "SYST\r\n");
   ftp_transmitter = ftp_reciver.try_advance("215 UNIX Type: L8\r\n".as_bytes()).ok().unwrap();
-  assert_eq!(ftp_transmitter.get_system(), (&"UNIX".to_string(), &"L8".to_string()));
+  assert_eq!(ftp_transmitter.get_system(), Some((&"UNIX".to_string(), &"L8".to_string())));
 
-  ftp_reciver = ftp_transmitter.send_pwd_req(&mut tx_buff, &mut tx_count);
+  ftp_reciver = ftp_transmitter.send_pwd_req(&mut tx_buff, &mut tx_count).ok().unwrap();
   assert_eq!(str::from_utf8(&tx_buff[0 .. tx_count]).unwrap(), "PWD\r\n");
   ftp_transmitter = ftp_reciver.try_advance("257 \"/\" is the current directory\r\n".as_bytes()).ok().unwrap();
-  assert_eq!(ftp_transmitter.get_wd(), "/");
+  assert_eq!(ftp_transmitter.get_wd(), Some("/"));
 
-  ftp_reciver = ftp_transmitter.send_cwd_req(&mut tx_buff, &mut tx_count, "/pub/FreeBSD/releases/ISO-IMAGES/10.3");
+  ftp_reciver = ftp_transmitter.send_cwd_req(&mut tx_buff, &mut tx_count, "/pub/FreeBSD/releases/ISO-IMAGES/10.3").ok().unwrap();
   assert_eq!(str::from_utf8(&tx_buff[0 .. tx_count]).unwrap(), "CWD /pub/FreeBSD/releases/ISO-IMAGES/10.3\r\n");
   ftp_transmitter = ftp_reciver.try_advance("250 Directory successfully changed.\r\n".as_bytes()).ok().unwrap();
-  assert_eq!(ftp_transmitter.get_wd(), "/pub/FreeBSD/releases/ISO-IMAGES/10.3");
+  assert_eq!(ftp_transmitter.get_wd(), Some("/pub/FreeBSD/releases/ISO-IMAGES/10.3"));
 
-  ftp_reciver = ftp_transmitter.send_pasv_req(&mut tx_buff, &mut tx_count);
+  ftp_reciver = ftp_transmitter.send_pasv_req(&mut tx_buff, &mut tx_count).ok().unwrap();
   assert_eq!(str::from_utf8(&tx_buff[0 .. tx_count]).unwrap(), "PASV\r\n");
   ftp_transmitter = ftp_reciver.try_advance("227 Entering Passive Mode (77,88,40,106,195,70).\r\n".as_bytes()).ok().unwrap();
 
-  assert_eq!(ftp_transmitter.take_endpoint(), (Ipv4Addr::new(77, 88, 40, 106), 49990));
+  assert_eq!(ftp_transmitter.take_endpoint(), Some(SocketAddr::V4(SocketAddrV4::new(Ipv4Addr::new(77, 88, 40, 106), 49990))));
 
-  ftp_reciver = ftp_transmitter.send_list_req(&mut tx_buff, &mut tx_count);
+  ftp_reciver = ftp_transmitter.send_list_req(&mut tx_buff, &mut tx_count, Some("-l")).ok().unwrap();
   assert_eq!(str::from_utf8(&tx_buff[0 .. tx_count]).unwrap(), "LIST -l\r\n");
 
   let listing = "-rw-r--r--    1 ftp      ftp          5430 Jul 19  2014 favicon.ico\r
@@ -67,16 +70,16 @@ drwxr-xr-x    3 ftp      ftp             3 Jul 19  2014 pub\r\n";
   let listing_tx = "150 Here comes the directory listing.\r\n";
 
   ftp_transmitter = ftp_reciver.try_advance(listing_tx.as_bytes()).ok().unwrap();
-  let list = ftp_transmitter.parse_list(listing.as_bytes()).unwrap();
+  let list = files(ftp_transmitter.parse_list(listing.as_bytes()));
   assert_eq!(list.len(), 3);
-  assert_eq!(list[0], RemoteFile { kind: RemoteFileKind::File, size: 5430,  name: "favicon.ico".to_string() } );
-  assert_eq!(list[1], RemoteFile { kind: RemoteFileKind::File, size: 660,  name: "index.html".to_string() } );
-  assert_eq!(list[2], RemoteFile { kind: RemoteFileKind::Directory, size: 3,  name: "pub".to_string() } );
+  assert_eq!((&list[0].kind, list[0].size, list[0].name.as_str()), (&RemoteFileKind::File, 5430, "favicon.ico"));
+  assert_eq!((&list[1].kind, list[1].size, list[1].name.as_str()), (&RemoteFileKind::File, 660, "index.html"));
+  assert_eq!((&list[2].kind, list[2].size, list[2].name.as_str()), (&RemoteFileKind::Directory, 3, "pub"));
 
   ftp_transmitter = ftp_transmitter.to_receiver().try_advance("226 Directory send OK.\r\n".as_bytes())
     .ok().unwrap();
 
-  ftp_reciver = ftp_transmitter.send_get_req(&mut tx_buff, &mut tx_count, "/a/b/favicon.ico");
+  ftp_reciver = ftp_transmitter.send_get_req(&mut tx_buff, &mut tx_count, "/a/b/favicon.ico").ok().unwrap();
   assert_eq!(str::from_utf8(&tx_buff[0 .. tx_count]).unwrap(), "RETR /a/b/favicon.ico\r\n");
 
   let _ = ftp_reciver.try_advance("150 Opening BINARY mode data connection for /a/b/favicon.ico (4259 bytes).\r\n".as_bytes())
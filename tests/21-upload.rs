@@ -0,0 +1,27 @@
+extern crate protocol_ftp_client;
+
+mod common;
+
+use common::logged_in;
+use std::str;
+
+#[test]
+fn upload_sample() {
+  let mut tx_buff:[u8; 1024] = [0; 1024];
+  let mut tx_count = 0;
+
+  let ftp_transmitter = logged_in(&mut tx_buff, &mut tx_count);
+
+  let ftp_reciver = ftp_transmitter.send_put_req(&mut tx_buff, &mut tx_count, "/drop/build-42.tar.gz").ok().unwrap();
+  assert_eq!(str::from_utf8(&tx_buff[0 .. tx_count]).unwrap(), "STOR /drop/build-42.tar.gz\r\n");
+
+  let ftp_transmitter = ftp_reciver.try_advance("150 Ok to send data.\r\n".as_bytes()).ok().unwrap()
+    .to_receiver().try_advance("226 Transfer complete.\r\n".as_bytes()).ok().unwrap();
+
+  let ftp_reciver = ftp_transmitter.send_put_req(&mut tx_buff, &mut tx_count, "build-43.tar.gz").ok().unwrap();
+  assert_eq!(str::from_utf8(&tx_buff[0 .. tx_count]).unwrap(), "STOR build-43.tar.gz\r\n");
+
+  let _ = ftp_reciver.try_advance("125 Data connection already open; transfer starting.\r\n".as_bytes()).ok().unwrap()
+    .to_receiver().try_advance("226 Closing data connection.\r\n".as_bytes()).ok().unwrap()
+    .send_pwd_req(&mut tx_buff, &mut tx_count).ok().unwrap();
+}
@@ -0,0 +1,44 @@
+extern crate protocol_ftp_client;
+
+mod common;
+
+use common::logged_in;
+use std::str;
+
+#[test]
+fn resume_download() {
+  let mut tx_buff:[u8; 1024] = [0; 1024];
+  let mut tx_count = 0;
+
+  let ftp_reciver = logged_in(&mut tx_buff, &mut tx_count)
+    .send_rest_req(&mut tx_buff, &mut tx_count, 3221225472).ok().unwrap();
+  assert_eq!(str::from_utf8(&tx_buff[0 .. tx_count]).unwrap(), "REST 3221225472\r\n");
+
+  let ftp_transmitter = ftp_reciver
+    .try_advance("350 Restart position accepted (3221225472).\r\n".as_bytes()).ok().unwrap();
+  assert_eq!(ftp_transmitter.get_rest_offset(), Some(3221225472));
+
+  let ftp_reciver = ftp_transmitter.send_get_req(&mut tx_buff, &mut tx_count, "FreeBSD-10.3-RELEASE-amd64-dvd1.iso").ok().unwrap();
+  assert_eq!(str::from_utf8(&tx_buff[0 .. tx_count]).unwrap(), "RETR FreeBSD-10.3-RELEASE-amd64-dvd1.iso\r\n");
+
+  let _ = ftp_reciver
+    .try_advance("150 Opening BINARY mode data connection for FreeBSD-10.3-RELEASE-amd64-dvd1.iso.\r\n".as_bytes()).ok().unwrap()
+    .to_receiver().try_advance("226 Transfer complete\r\n".as_bytes()).ok().unwrap()
+    .send_pwd_req(&mut tx_buff, &mut tx_count).ok().unwrap();
+}
+
+#[test]
+fn resume_append() {
+  let mut tx_buff:[u8; 1024] = [0; 1024];
+  let mut tx_count = 0;
+
+  let ftp_reciver = logged_in(&mut tx_buff, &mut tx_count)
+    .send_rest_req(&mut tx_buff, &mut tx_count, 1024).ok().unwrap()
+    .try_advance("350 Restarting at 1024. Send STORE or RETRIEVE.\r\n".as_bytes()).ok().unwrap()
+    .send_append_req(&mut tx_buff, &mut tx_count, "build.log").ok().unwrap();
+  assert_eq!(str::from_utf8(&tx_buff[0 .. tx_count]).unwrap(), "APPE build.log\r\n");
+
+  let _ = ftp_reciver
+    .try_advance("150 Ok to send data.\r\n".as_bytes()).ok().unwrap()
+    .to_receiver().try_advance("226 Transfer complete.\r\n".as_bytes()).ok().unwrap();
+}
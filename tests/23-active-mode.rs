@@ -0,0 +1,41 @@
+extern crate protocol_ftp_client;
+
+mod common;
+
+use common::logged_in;
+use std::str;
+use std::net::{Ipv4Addr, Ipv6Addr, SocketAddr, SocketAddrV6};
+
+#[test]
+fn port_sample() {
+  let mut tx_buff:[u8; 1024] = [0; 1024];
+  let mut tx_count = 0;
+
+  let ftp_reciver = logged_in(&mut tx_buff, &mut tx_count)
+    .send_port_req(&mut tx_buff, &mut tx_count, Ipv4Addr::new(192, 168, 1, 2), 49990).ok().unwrap();
+  assert_eq!(str::from_utf8(&tx_buff[0 .. tx_count]).unwrap(), "PORT 192,168,1,2,195,70\r\n");
+
+  let ftp_reciver = ftp_reciver.try_advance("200 PORT command successful.\r\n".as_bytes()).ok().unwrap()
+    .send_list_req(&mut tx_buff, &mut tx_count, None).ok().unwrap();
+
+  let _ = ftp_reciver.try_advance("150 Here comes the directory listing.\r\n".as_bytes()).ok().unwrap()
+    .to_receiver().try_advance("226 Directory send OK.\r\n".as_bytes()).ok().unwrap();
+}
+
+#[test]
+fn eprt_sample() {
+  let mut tx_buff:[u8; 1024] = [0; 1024];
+  let mut tx_count = 0;
+
+  let ftp_reciver = logged_in(&mut tx_buff, &mut tx_count)
+    .send_eprt_req(&mut tx_buff, &mut tx_count, "132.235.1.2:6275".parse().unwrap()).ok().unwrap();
+  assert_eq!(str::from_utf8(&tx_buff[0 .. tx_count]).unwrap(), "EPRT |1|132.235.1.2|6275|\r\n");
+
+  let ftp_reciver = ftp_reciver.try_advance("200 EPRT command successful.\r\n".as_bytes()).ok().unwrap()
+    .send_eprt_req(&mut tx_buff, &mut tx_count,
+                   SocketAddr::V6(SocketAddrV6::new(Ipv6Addr::new(0x1080, 0, 0, 0, 0x8, 0x800, 0x200C, 0x417A), 5282, 0, 0))).ok().unwrap();
+  assert_eq!(str::from_utf8(&tx_buff[0 .. tx_count]).unwrap(), "EPRT |2|1080::8:800:200c:417a|5282|\r\n");
+
+  let _ = ftp_reciver.try_advance("200 EPRT command successful.\r\n".as_bytes()).ok().unwrap()
+    .send_get_req(&mut tx_buff, &mut tx_count, "file.txt").ok().unwrap();
+}
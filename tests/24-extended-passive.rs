@@ -0,0 +1,61 @@
+extern crate protocol_ftp_client;
+
+mod common;
+
+use protocol_ftp_client::*;
+use common::logged_in;
+use std::str;
+use std::net::{IpAddr, Ipv4Addr, Ipv6Addr, SocketAddr};
+
+#[test]
+fn epsv_ipv6() {
+  let mut tx_buff:[u8; 1024] = [0; 1024];
+  let mut tx_count = 0;
+
+  let peer = IpAddr::V6(Ipv6Addr::new(0x2a02, 0x6b8, 0, 0, 0, 0, 0, 0x106));
+  let ftp_reciver = logged_in(&mut tx_buff, &mut tx_count)
+    .send_epsv_req(&mut tx_buff, &mut tx_count, peer).ok().unwrap();
+  assert_eq!(str::from_utf8(&tx_buff[0 .. tx_count]).unwrap(), "EPSV\r\n");
+
+  let mut ftp_transmitter = ftp_reciver
+    .try_advance("229 Entering Extended Passive Mode (|||49990|)\r\n".as_bytes()).ok().unwrap();
+  assert_eq!(ftp_transmitter.take_endpoint(), Some(SocketAddr::new(peer, 49990)));
+}
+
+#[test]
+fn epsv_custom_delimiter() {
+  let mut tx_buff:[u8; 1024] = [0; 1024];
+  let mut tx_count = 0;
+
+  let peer = IpAddr::V4(Ipv4Addr::new(77, 88, 40, 106));
+  let mut ftp_transmitter = logged_in(&mut tx_buff, &mut tx_count)
+    .send_epsv_req(&mut tx_buff, &mut tx_count, peer).ok().unwrap()
+    .try_advance("229 Extended Passive Mode OK (!!!6446!)\r\n".as_bytes()).ok().unwrap();
+  assert_eq!(ftp_transmitter.take_endpoint(), Some(SocketAddr::new(peer, 6446)));
+}
+
+#[test]
+fn epsv_garbage() {
+  let mut tx_buff:[u8; 1024] = [0; 1024];
+  let mut tx_count = 0;
+
+  let peer = IpAddr::V4(Ipv4Addr::new(77, 88, 40, 106));
+  let mut ftp_reciver = logged_in(&mut tx_buff, &mut tx_count)
+    .send_epsv_req(&mut tx_buff, &mut tx_count, peer).ok().unwrap()
+    .try_advance("229 Entering Extended Passive Mode (|||99999|)\r\n".as_bytes()).err().unwrap();
+  assert_eq!(ftp_reciver.take_error().unwrap(), FtpError::GarbageData);
+}
+
+#[test]
+fn epsv_mismatched_delimiters() {
+  let mut tx_buff:[u8; 1024] = [0; 1024];
+  let mut tx_count = 0;
+
+  let peer = IpAddr::V4(Ipv4Addr::new(77, 88, 40, 106));
+  for reply in ["229 Entering Extended Passive Mode (123456|)\r\n", "229 Entering Extended Passive Mode (||!6446|)\r\n"].iter() {
+    let mut ftp_reciver = logged_in(&mut tx_buff, &mut tx_count)
+      .send_epsv_req(&mut tx_buff, &mut tx_count, peer).ok().unwrap()
+      .try_advance(reply.as_bytes()).err().unwrap();
+    assert_eq!(ftp_reciver.take_error().unwrap(), FtpError::GarbageData);
+  }
+}
@@ -0,0 +1,58 @@
+extern crate protocol_ftp_client;
+
+mod common;
+
+use protocol_ftp_client::*;
+use common::logged_in;
+use std::str;
+
+#[test]
+fn mkd_rmd() {
+  let mut tx_buff:[u8; 1024] = [0; 1024];
+  let mut tx_count = 0;
+
+  let ftp_reciver = logged_in(&mut tx_buff, &mut tx_count)
+    .send_mkd_req(&mut tx_buff, &mut tx_count, "releases/2026-10").ok().unwrap();
+  assert_eq!(str::from_utf8(&tx_buff[0 .. tx_count]).unwrap(), "MKD releases/2026-10\r\n");
+
+  let ftp_transmitter = ftp_reciver
+    .try_advance("257 \"/mirror/releases/2026-10\" created\r\n".as_bytes()).ok().unwrap();
+  assert_eq!(ftp_transmitter.get_created_dir(), Some("/mirror/releases/2026-10"));
+  assert_eq!(ftp_transmitter.get_wd(), None);
+
+  let ftp_reciver = ftp_transmitter.send_rmd_req(&mut tx_buff, &mut tx_count, "/mirror/releases/2016-10").ok().unwrap();
+  assert_eq!(str::from_utf8(&tx_buff[0 .. tx_count]).unwrap(), "RMD /mirror/releases/2016-10\r\n");
+
+  let mut ftp_reciver = ftp_reciver
+    .try_advance("250 Remove directory operation successful.\r\n".as_bytes()).ok().unwrap()
+    .send_rmd_req(&mut tx_buff, &mut tx_count, "/mirror/releases/2015-10").ok().unwrap()
+    .try_advance("550 Remove directory operation failed.\r\n".as_bytes()).err().unwrap();
+  assert_eq!(ftp_reciver.take_error().unwrap(), FtpError::ServerError(550, "Remove directory operation failed.".to_string()));
+}
+
+#[test]
+fn cdup() {
+  let mut tx_buff:[u8; 1024] = [0; 1024];
+  let mut tx_count = 0;
+
+  let ftp_transmitter = logged_in(&mut tx_buff, &mut tx_count)
+    .send_cwd_req(&mut tx_buff, &mut tx_count, "/pub/FreeBSD/releases/").ok().unwrap()
+    .try_advance("250 Directory successfully changed.\r\n".as_bytes()).ok().unwrap();
+
+  let ftp_reciver = ftp_transmitter.send_cdup_req(&mut tx_buff, &mut tx_count).ok().unwrap();
+  assert_eq!(str::from_utf8(&tx_buff[0 .. tx_count]).unwrap(), "CDUP\r\n");
+  let ftp_transmitter = ftp_reciver.try_advance("250 Directory successfully changed.\r\n".as_bytes()).ok().unwrap();
+  assert_eq!(ftp_transmitter.get_wd(), Some("/pub/FreeBSD"));
+
+  let ftp_transmitter = ftp_transmitter.send_cdup_req(&mut tx_buff, &mut tx_count).ok().unwrap()
+    .try_advance("200 CDUP command successful.\r\n".as_bytes()).ok().unwrap()
+    .send_cdup_req(&mut tx_buff, &mut tx_count).ok().unwrap()
+    .try_advance("250 Directory successfully changed.\r\n".as_bytes()).ok().unwrap();
+  assert_eq!(ftp_transmitter.get_wd(), Some("/"));
+
+  let ftp_transmitter = ftp_transmitter.send_cwd_req(&mut tx_buff, &mut tx_count, "pub").ok().unwrap()
+    .try_advance("250 Directory successfully changed.\r\n".as_bytes()).ok().unwrap()
+    .send_cdup_req(&mut tx_buff, &mut tx_count).ok().unwrap()
+    .try_advance("250 Directory successfully changed.\r\n".as_bytes()).ok().unwrap();
+  assert_eq!(ftp_transmitter.get_wd(), None);
+}
@@ -0,0 +1,58 @@
+extern crate protocol_ftp_client;
+
+mod common;
+
+use protocol_ftp_client::*;
+use common::logged_in;
+use std::str;
+
+#[test]
+fn atomic_publish() {
+  let mut tx_buff:[u8; 1024] = [0; 1024];
+  let mut tx_count = 0;
+
+  let ftp_reciver = logged_in(&mut tx_buff, &mut tx_count)
+    .send_rnfr_req(&mut tx_buff, &mut tx_count, "release.tar.gz.part").ok().unwrap();
+  assert_eq!(str::from_utf8(&tx_buff[0 .. tx_count]).unwrap(), "RNFR release.tar.gz.part\r\n");
+
+  let mut ftp_transmitter = ftp_reciver
+    .try_advance("350 Ready for destination name.\r\n".as_bytes()).ok().unwrap()
+    .send_pwd_req(&mut tx_buff, &mut tx_count).err().unwrap();
+  assert_eq!(ftp_transmitter.take_error().unwrap(), FtpError::InvalidState {
+    current: "[state: rename-from-confirmed(release.tar.gz.part)]".to_string(),
+    attempted: "send_pwd_req".to_string(),
+  });
+
+  let ftp_reciver = ftp_transmitter.send_rnto_req(&mut tx_buff, &mut tx_count, "release.tar.gz").ok().unwrap();
+  assert_eq!(str::from_utf8(&tx_buff[0 .. tx_count]).unwrap(), "RNTO release.tar.gz\r\n");
+
+  let ftp_reciver = ftp_reciver
+    .try_advance("250 Rename successful.\r\n".as_bytes()).ok().unwrap()
+    .send_dele_req(&mut tx_buff, &mut tx_count, "release-old.tar.gz").ok().unwrap();
+  assert_eq!(str::from_utf8(&tx_buff[0 .. tx_count]).unwrap(), "DELE release-old.tar.gz\r\n");
+
+  let _ = ftp_reciver.try_advance("250 Delete operation successful.\r\n".as_bytes()).ok().unwrap()
+    .send_pwd_req(&mut tx_buff, &mut tx_count).ok().unwrap();
+}
+
+#[test]
+fn rnto_requires_rnfr() {
+  let mut tx_buff:[u8; 1024] = [0; 1024];
+  let mut tx_count = 0;
+
+  let mut ftp_transmitter = logged_in(&mut tx_buff, &mut tx_count)
+    .send_rnto_req(&mut tx_buff, &mut tx_count, "release.tar.gz").err().unwrap();
+  assert_eq!(ftp_transmitter.take_error().unwrap(), FtpError::InvalidState {
+    current: "[state: authorized]".to_string(),
+    attempted: "send_rnto_req".to_string(),
+  });
+
+  let mut ftp_reciver = ftp_transmitter
+    .send_rnfr_req(&mut tx_buff, &mut tx_count, "missing.tar.gz").ok().unwrap()
+    .try_advance("550 RNFR command failed.\r\n".as_bytes()).err().unwrap();
+  assert_eq!(ftp_reciver.take_error().unwrap(), FtpError::ServerError(550, "RNFR command failed.".to_string()));
+
+  let mut ftp_transmitter = ftp_reciver.to_transmitter()
+    .send_rnto_req(&mut tx_buff, &mut tx_count, "release.tar.gz").err().unwrap();
+  assert!(ftp_transmitter.take_error().is_some());
+}
@@ -0,0 +1,69 @@
+extern crate protocol_ftp_client;
+
+mod common;
+
+use protocol_ftp_client::*;
+use common::logged_in;
+use std::str;
+use std::time::{Duration, UNIX_EPOCH};
+
+#[test]
+fn size_and_mdtm() {
+  let mut tx_buff:[u8; 1024] = [0; 1024];
+  let mut tx_count = 0;
+
+  let ftp_reciver = logged_in(&mut tx_buff, &mut tx_count)
+    .send_size_req(&mut tx_buff, &mut tx_count, "FreeBSD-10.3-RELEASE-amd64-dvd1.iso").ok().unwrap();
+  assert_eq!(str::from_utf8(&tx_buff[0 .. tx_count]).unwrap(), "SIZE FreeBSD-10.3-RELEASE-amd64-dvd1.iso\r\n");
+
+  let ftp_transmitter = ftp_reciver.try_advance("213 5037555712\r\n".as_bytes()).ok().unwrap();
+  assert_eq!(ftp_transmitter.get_size(), Some(5037555712));
+  assert_eq!(ftp_transmitter.get_modification_time(), None);
+
+  let ftp_reciver = ftp_transmitter
+    .send_mdtm_req(&mut tx_buff, &mut tx_count, "FreeBSD-10.3-RELEASE-amd64-dvd1.iso").ok().unwrap();
+  assert_eq!(str::from_utf8(&tx_buff[0 .. tx_count]).unwrap(), "MDTM FreeBSD-10.3-RELEASE-amd64-dvd1.iso\r\n");
+
+  let ftp_transmitter = ftp_reciver.try_advance("213 20160325043155\r\n".as_bytes()).ok().unwrap();
+  let time = ftp_transmitter.get_modification_time().unwrap().clone();
+  assert_eq!(time, Timestamp { year: 2016, month: 3, day: 25, hour: 4, minute: 31, second: 55, millis: 0 });
+  assert_eq!(ftp_transmitter.get_size(), Some(5037555712));
+
+  let mut ftp_reciver = ftp_transmitter
+    .send_size_req(&mut tx_buff, &mut tx_count, "pub").ok().unwrap()
+    .try_advance("550 Could not get file size.\r\n".as_bytes()).err().unwrap();
+  assert_eq!(ftp_reciver.take_error().unwrap(), FtpError::ServerError(550, "Could not get file size.".to_string()));
+
+  let mut ftp_reciver = ftp_reciver.to_transmitter()
+    .send_mdtm_req(&mut tx_buff, &mut tx_count, "index.html").ok().unwrap()
+    .try_advance("213 2016-03-25 04:31\r\n".as_bytes()).err().unwrap();
+  assert_eq!(ftp_reciver.take_error().unwrap(), FtpError::GarbageData);
+
+  let mut ftp_reciver = logged_in(&mut tx_buff, &mut tx_count)
+    .send_mdtm_req(&mut tx_buff, &mut tx_count, "index.html").ok().unwrap()
+    .try_advance("213 ٢٠٢٦1018120000\r\n".as_bytes()).err().unwrap();
+  assert_eq!(ftp_reciver.take_error().unwrap(), FtpError::GarbageData);
+}
+
+#[test]
+fn time_val() {
+  let time = Timestamp::parse_time_val("20140719000000").unwrap();
+  assert_eq!(time.to_system_time(), UNIX_EPOCH + Duration::from_secs(1405728000));
+  assert_eq!(format!("{}", time), "2014-07-19T00:00:00.000Z");
+
+  let time = Timestamp::parse_time_val("20000229123015.5").unwrap();
+  assert_eq!(time.millis, 500);
+  assert_eq!(time.to_system_time(), UNIX_EPOCH + Duration::from_millis(951827415500));
+
+  let time = Timestamp::parse_time_val("19691231235959.123").unwrap();
+  assert_eq!(time.to_system_time(), UNIX_EPOCH - Duration::from_millis(877));
+
+  assert!(Timestamp::parse_time_val("20141319000000").is_none());
+  assert!(Timestamp::parse_time_val("20260230120000").is_none());
+  assert!(Timestamp::parse_time_val("21000229120000").is_none());
+  assert!(Timestamp::parse_time_val("20140431120000").is_none());
+  assert!(Timestamp::parse_time_val("2014071900000").is_none());
+  assert!(Timestamp::parse_time_val("20140719000000 ").is_none());
+  assert!(Timestamp::parse_time_val("٢٠٢٦1018120000").is_none());
+  assert!(Timestamp::parse_time_val("20261018120000.٥").is_none());
+}
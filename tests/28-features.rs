@@ -0,0 +1,80 @@
+extern crate protocol_ftp_client;
+
+mod common;
+
+use protocol_ftp_client::*;
+use common::logged_in;
+use std::str;
+
+#[test]
+fn features_sample() {
+  let mut tx_buff:[u8; 1024] = [0; 1024];
+  let mut tx_count = 0;
+
+  let ftp_transmitter = logged_in(&mut tx_buff, &mut tx_count);
+  assert_eq!(ftp_transmitter.get_features(), None);
+
+  let ftp_reciver = ftp_transmitter.send_feat_req(&mut tx_buff, &mut tx_count).ok().unwrap();
+  assert_eq!(str::from_utf8(&tx_buff[0 .. tx_count]).unwrap(), "FEAT\r\n");
+
+  let ftp_transmitter = ftp_reciver.try_advance("211-Features:\r\n EPRT\r\n EPSV\r\n MDTM\r\n PASV\r\n REST STREAM\r\n SIZE\r\n TVFS\r\n UTF8\r\n MLST type*;size*;modify*;perm;\r\n LANG EN*;FR\r\n211 End\r\n".as_bytes()).ok().unwrap();
+  {
+    let features = ftp_transmitter.get_features().unwrap();
+    assert!(features.eprt && features.epsv && features.mdtm && features.rest_stream);
+    assert!(features.size && features.tvfs && features.utf8);
+    assert_eq!(features.mlst, Some(vec!["type*".to_string(), "size*".to_string(), "modify*".to_string(), "perm".to_string()]));
+    assert_eq!(features.other.get("PASV"), Some(&"".to_string()));
+    assert_eq!(features.other.get("LANG"), Some(&"EN*;FR".to_string()));
+  }
+
+  let _ = ftp_transmitter.send_size_req(&mut tx_buff, &mut tx_count, "file.txt").ok().unwrap();
+  assert_eq!(str::from_utf8(&tx_buff[0 .. tx_count]).unwrap(), "SIZE file.txt\r\n");
+}
+
+#[test]
+fn no_features() {
+  let mut tx_buff:[u8; 1024] = [0; 1024];
+  let mut tx_count = 0;
+
+  let ftp_transmitter = logged_in(&mut tx_buff, &mut tx_count)
+    .send_feat_req(&mut tx_buff, &mut tx_count).ok().unwrap()
+    .try_advance("211 No features\r\n".as_bytes()).ok().unwrap();
+  assert_eq!(ftp_transmitter.get_features(), Some(&Features::default()));
+}
+
+#[test]
+fn unsupported_commands_refused() {
+  let mut tx_buff:[u8; 1024] = [0; 1024];
+  let mut tx_count = 0;
+
+  let ftp_transmitter = logged_in(&mut tx_buff, &mut tx_count)
+    .send_feat_req(&mut tx_buff, &mut tx_count).ok().unwrap()
+    .try_advance("211-Extensions supported:\r\n SIZE\r\n211 END\r\n".as_bytes()).ok().unwrap();
+
+  tx_count = 0;
+  let mut ftp_transmitter = ftp_transmitter.send_mdtm_req(&mut tx_buff, &mut tx_count, "file.txt").err().unwrap();
+  assert_eq!(ftp_transmitter.take_error(), Some(FtpError::NotSupported("MDTM".to_string())));
+  assert_eq!(tx_count, 0);
+
+  let mut ftp_transmitter = ftp_transmitter.send_rest_req(&mut tx_buff, &mut tx_count, 100).err().unwrap();
+  assert_eq!(ftp_transmitter.take_error(), Some(FtpError::NotSupported("REST STREAM".to_string())));
+
+  let mut ftp_transmitter = ftp_transmitter.send_epsv_req(&mut tx_buff, &mut tx_count, "127.0.0.1".parse().unwrap()).err().unwrap();
+  assert_eq!(ftp_transmitter.take_error(), Some(FtpError::NotSupported("EPSV".to_string())));
+
+  let mut ftp_transmitter = ftp_transmitter.send_eprt_req(&mut tx_buff, &mut tx_count, "127.0.0.1:1025".parse().unwrap()).err().unwrap();
+  assert_eq!(ftp_transmitter.take_error(), Some(FtpError::NotSupported("EPRT".to_string())));
+  assert_eq!(tx_count, 0);
+
+  let _ = ftp_transmitter.send_size_req(&mut tx_buff, &mut tx_count, "file.txt").ok().unwrap();
+}
+
+#[test]
+fn unknown_features_allow_everything() {
+  let mut tx_buff:[u8; 1024] = [0; 1024];
+  let mut tx_count = 0;
+
+  let _ = logged_in(&mut tx_buff, &mut tx_count)
+    .send_mdtm_req(&mut tx_buff, &mut tx_count, "file.txt").ok().unwrap();
+  assert_eq!(str::from_utf8(&tx_buff[0 .. tx_count]).unwrap(), "MDTM file.txt\r\n");
+}
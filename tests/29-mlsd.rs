@@ -0,0 +1,102 @@
+extern crate protocol_ftp_client;
+
+mod common;
+
+use protocol_ftp_client::*;
+use common::{files, logged_in};
+use std::str;
+
+#[test]
+fn mlsd_sample() {
+  let mut tx_buff:[u8; 1024] = [0; 1024];
+  let mut tx_count = 0;
+
+  let ftp_reciver = logged_in(&mut tx_buff, &mut tx_count)
+    .send_mlsd_req(&mut tx_buff, &mut tx_count, None).ok().unwrap();
+  assert_eq!(str::from_utf8(&tx_buff[0 .. tx_count]).unwrap(), "MLSD\r\n");
+
+  let ftp_transmitter = ftp_reciver.try_advance("150 Here comes the directory listing.\r\n".as_bytes()).ok().unwrap();
+  let listing = "type=cdir;sizd=4096;modify=20160101120000;perm=flcdmpe;unique=801U5; .\r\n\
+                 type=pdir;sizd=4096;modify=20151231235959;perm=flcdmpe; ..\r\n\
+                 Type=file;Size=1830;Modify=20140719000000.500;Perm=adfrw;UNIX.mode=0644;UNIX.owner=ftp; my file; v2.txt\r\n\
+                 type=dir;modify=20140719000000;perm=flcdmpe; pub\r\n\
+                 type=OS.unix=slink:/etc/motd;modify=20140719000000; motd\n";
+  let list = files(ftp_transmitter.parse_mlsd(listing.as_bytes()));
+  assert_eq!(list.len(), 5);
+
+  assert_eq!(list[0].kind, RemoteFileKind::CurrentDirectory);
+  assert_eq!(list[0].name, ".");
+  assert_eq!(list[0].unique, Some("801U5".to_string()));
+  assert_eq!(list[0].facts.get("sizd"), Some(&"4096".to_string()));
+  assert_eq!(list[1].kind, RemoteFileKind::ParentDirectory);
+
+  assert_eq!(list[2].kind, RemoteFileKind::File);
+  assert_eq!(list[2].name, "my file; v2.txt");
+  assert_eq!(list[2].size, 1830);
+  assert_eq!(list[2].perm, Some("adfrw".to_string()));
+  assert_eq!(list[2].unix_mode, Some(0o644));
+  assert_eq!(format!("{}", list[2].modified.unwrap()), "2014-07-19T00:00:00.500Z");
+  assert_eq!(list[2].facts.get("unix.owner"), Some(&"ftp".to_string()));
+
+  assert_eq!(list[3].kind, RemoteFileKind::Directory);
+  assert_eq!(list[4].kind, RemoteFileKind::Other("OS.unix=slink:/etc/motd".to_string()));
+  assert_eq!(list[4].name, "motd");
+
+  let _ = ftp_transmitter.to_receiver().try_advance("226 Directory send OK.\r\n".as_bytes()).ok().unwrap()
+    .send_mlsd_req(&mut tx_buff, &mut tx_count, Some("/pub")).ok().unwrap();
+  assert_eq!(str::from_utf8(&tx_buff[0 .. tx_count]).unwrap(), "MLSD /pub\r\n");
+}
+
+#[test]
+fn garbage_mlsd_line() {
+  let mut tx_buff:[u8; 1024] = [0; 1024];
+  let mut tx_count = 0;
+
+  let ftp_transmitter = logged_in(&mut tx_buff, &mut tx_count);
+  assert_eq!(ftp_transmitter.parse_mlsd("type=file;size=abc; file.txt\r\n".as_bytes()), vec![Err(FtpError::GarbageData)]);
+  assert_eq!(ftp_transmitter.parse_mlsd("type=file;size=10;file.txt\r\n".as_bytes()), vec![Err(FtpError::GarbageData)]);
+  assert_eq!(ftp_transmitter.parse_mlsd("-rw-r--r-- 1 ftp ftp 10 Jul 19 2014 file.txt\r\n".as_bytes()), vec![Err(FtpError::GarbageData)]);
+
+  // the rest of listing is kept
+  let listing = "type=file;size=10;modify=bogus; bad.txt\r\ntype=file;size=10; good.txt\r\n";
+  let list = ftp_transmitter.parse_mlsd(listing.as_bytes());
+  assert_eq!(list.len(), 2);
+  assert_eq!(list[0], Err(FtpError::GarbageData));
+  assert_eq!(list[1].as_ref().map(|file| file.name.as_str()), Ok("good.txt"));
+}
+
+#[test]
+fn mlst_sample() {
+  let mut tx_buff:[u8; 1024] = [0; 1024];
+  let mut tx_count = 0;
+
+  let ftp_reciver = logged_in(&mut tx_buff, &mut tx_count)
+    .send_mlst_req(&mut tx_buff, &mut tx_count, Some("/pub/file.txt")).ok().unwrap();
+  assert_eq!(str::from_utf8(&tx_buff[0 .. tx_count]).unwrap(), "MLST /pub/file.txt\r\n");
+
+  let ftp_transmitter = ftp_reciver.try_advance("250-Listing /pub/file.txt\r\n type=file;size=42;modify=20140719000000; /pub/file.txt\r\n250 End\r\n".as_bytes()).ok().unwrap();
+  let expected = RemoteFile {
+    kind: RemoteFileKind::File,
+    size: 42,
+    name: "/pub/file.txt".to_string(),
+    raw_name: "/pub/file.txt".as_bytes().to_vec(),
+    modified: Timestamp::parse_time_val("20140719000000"),
+    ..RemoteFile::default()
+  };
+  assert_eq!(ftp_transmitter.get_file_facts(), Some(&expected));
+
+  let _ = ftp_transmitter.send_mlst_req(&mut tx_buff, &mut tx_count, None).ok().unwrap();
+  assert_eq!(str::from_utf8(&tx_buff[0 .. tx_count]).unwrap(), "MLST\r\n");
+}
+
+#[test]
+fn mlsd_not_supported() {
+  let mut tx_buff:[u8; 1024] = [0; 1024];
+  let mut tx_count = 0;
+
+  let mut ftp_transmitter = logged_in(&mut tx_buff, &mut tx_count)
+    .send_feat_req(&mut tx_buff, &mut tx_count).ok().unwrap()
+    .try_advance("211-Features:\r\n SIZE\r\n211 End\r\n".as_bytes()).ok().unwrap()
+    .send_mlsd_req(&mut tx_buff, &mut tx_count, None).err().unwrap();
+  assert_eq!(ftp_transmitter.take_error(), Some(FtpError::NotSupported("MLST".to_string())));
+}
@@ -0,0 +1,45 @@
+extern crate protocol_ftp_client;
+
+mod common;
+
+use protocol_ftp_client::*;
+use common::logged_in;
+use std::str;
+
+#[test]
+fn nlst_sample() {
+  let mut tx_buff:[u8; 1024] = [0; 1024];
+  let mut tx_count = 0;
+
+  let ftp_reciver = logged_in(&mut tx_buff, &mut tx_count)
+    .send_nlst_req(&mut tx_buff, &mut tx_count, None).ok().unwrap();
+  assert_eq!(str::from_utf8(&tx_buff[0 .. tx_count]).unwrap(), "NLST\r\n");
+
+  let ftp_transmitter = ftp_reciver.try_advance("150 Here comes the directory listing.\r\n".as_bytes()).ok().unwrap();
+  let names: Vec<String> = ftp_transmitter.parse_nlst("favicon.ico\r\nindex.html\r\nmy file.txt\npub\r\n".as_bytes())
+    .into_iter().map(|name| name.name).collect();
+  assert_eq!(names, vec!["favicon.ico", "index.html", "my file.txt", "pub"]);
+  assert_eq!(ftp_transmitter.parse_nlst("".as_bytes()), Vec::new());
+
+  let _ = ftp_transmitter.to_receiver().try_advance("226 Directory send OK.\r\n".as_bytes()).ok().unwrap()
+    .send_nlst_req(&mut tx_buff, &mut tx_count, Some("/pub")).ok().unwrap();
+  assert_eq!(str::from_utf8(&tx_buff[0 .. tx_count]).unwrap(), "NLST /pub\r\n");
+}
+
+#[test]
+fn list_arguments() {
+  let mut tx_buff:[u8; 1024] = [0; 1024];
+  let mut tx_count = 0;
+
+  let _ = logged_in(&mut tx_buff, &mut tx_count)
+    .send_list_req(&mut tx_buff, &mut tx_count, None).ok().unwrap();
+  assert_eq!(str::from_utf8(&tx_buff[0 .. tx_count]).unwrap(), "LIST\r\n");
+
+  let _ = logged_in(&mut tx_buff, &mut tx_count)
+    .send_list_req(&mut tx_buff, &mut tx_count, Some("/pub")).ok().unwrap();
+  assert_eq!(str::from_utf8(&tx_buff[0 .. tx_count]).unwrap(), "LIST /pub\r\n");
+
+  let mut ftp_transmitter = logged_in(&mut tx_buff, &mut tx_count)
+    .send_list_req(&mut tx_buff, &mut tx_count, Some("/pub\r\nDELE x")).err().unwrap();
+  assert_eq!(ftp_transmitter.take_error(), Some(FtpError::InvalidArgument));
+}
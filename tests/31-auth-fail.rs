@@ -12,15 +12,15 @@ fn session_sample() {
 
   ftp_reciver = ftp_reciver
     .try_advance("220 Service ready for new user.\r\n".as_bytes()).ok().unwrap()
-    .send_login(&mut tx_buff, &mut tx_count, "user");
+    .send_login(&mut tx_buff, &mut tx_count, "user").ok().unwrap();
 
   ftp_reciver = ftp_reciver
     .try_advance("331 User name okay, need password for user.\r\n".as_bytes()).ok().unwrap()
-    .send_password(&mut tx_buff, &mut tx_count, "11");
+    .send_password(&mut tx_buff, &mut tx_count, "11").ok().unwrap();
 
   ftp_reciver = ftp_reciver.try_advance("530 Authentication failed.\r\n".as_bytes()).err().unwrap();
   assert_eq!(ftp_reciver.take_error().unwrap(), FtpError::AuthFailed);
 
-  ftp_reciver.to_transmitter().send_login(&mut tx_buff, &mut tx_count, "anonymous");
+  ftp_reciver.to_transmitter().send_login(&mut tx_buff, &mut tx_count, "anonymous").ok().unwrap();
 
 }
@@ -0,0 +1,61 @@
+extern crate protocol_ftp_client;
+
+mod common;
+
+use protocol_ftp_client::*;
+use common::logged_in;
+use std::str;
+
+#[test]
+fn file_not_found() {
+  let mut tx_buff:[u8; 1024] = [0; 1024];
+  let mut tx_count = 0;
+
+  let mut ftp_reciver = logged_in(&mut tx_buff, &mut tx_count)
+    .send_get_req(&mut tx_buff, &mut tx_count, "missing.iso").ok().unwrap()
+    .try_advance("550 No such file or directory.\r\n".as_bytes()).err().unwrap();
+  assert_eq!(ftp_reciver.take_error().unwrap(), FtpError::ServerError(550, "No such file or directory.".to_string()));
+
+  ftp_reciver = ftp_reciver.to_transmitter().send_pwd_req(&mut tx_buff, &mut tx_count).ok().unwrap();
+  assert_eq!(str::from_utf8(&tx_buff[0 .. tx_count]).unwrap(), "PWD\r\n");
+  let ftp_transmitter = ftp_reciver.try_advance("257 \"/\" is the current directory\r\n".as_bytes()).ok().unwrap();
+  assert_eq!(ftp_transmitter.get_wd(), Some("/"));
+}
+
+#[test]
+fn transfer_aborted() {
+  let mut tx_buff:[u8; 1024] = [0; 1024];
+  let mut tx_count = 0;
+
+  let mut ftp_reciver = logged_in(&mut tx_buff, &mut tx_count)
+    .send_get_req(&mut tx_buff, &mut tx_count, "big.iso").ok().unwrap()
+    .try_advance("150 Opening BINARY mode data connection for big.iso.\r\n".as_bytes()).ok().unwrap()
+    .to_receiver().try_advance("426 Connection closed; transfer aborted.\r\n".as_bytes()).err().unwrap();
+  assert_eq!(ftp_reciver.take_error().unwrap(), FtpError::ServerError(426, "Connection closed; transfer aborted.".to_string()));
+
+  ftp_reciver.to_transmitter().send_list_req(&mut tx_buff, &mut tx_count, Some("-l")).ok().unwrap();
+  assert_eq!(str::from_utf8(&tx_buff[0 .. tx_count]).unwrap(), "LIST -l\r\n");
+}
+
+#[test]
+fn service_not_available() {
+  let mut ftp_reciver = FtpReceiver::new()
+    .try_advance("421 Service not available, closing control connection.\r\n".as_bytes()).err().unwrap();
+  assert_eq!(ftp_reciver.take_error().unwrap(),
+             FtpError::ServerError(421, "Service not available, closing control connection.".to_string()));
+}
+
+#[test]
+fn unknown_user() {
+  let mut tx_buff:[u8; 1024] = [0; 1024];
+  let mut tx_count = 0;
+
+  let mut ftp_reciver = FtpReceiver::new()
+    .try_advance("220 Service ready for new user.\r\n".as_bytes()).ok().unwrap()
+    .send_login(&mut tx_buff, &mut tx_count, "nobody").ok().unwrap()
+    .try_advance("504 Security mechanism not understood.\r\n".as_bytes()).err().unwrap();
+  assert_eq!(ftp_reciver.take_error().unwrap(), FtpError::ServerError(504, "Security mechanism not understood.".to_string()));
+
+  ftp_reciver.to_transmitter().send_login(&mut tx_buff, &mut tx_count, "anonymous").ok().unwrap();
+  assert_eq!(str::from_utf8(&tx_buff[0 .. tx_count]).unwrap(), "USER anonymous\r\n");
+}
@@ -0,0 +1,44 @@
+extern crate protocol_ftp_client;
+
+use protocol_ftp_client::*;
+use std::str;
+
+#[test]
+fn list_before_login() {
+  let mut tx_buff:[u8; 1024] = [0; 1024];
+  let mut tx_count = 0;
+
+  let mut ftp_transmitter = FtpReceiver::new()
+    .try_advance("220 Service ready for new user.\r\n".as_bytes()).ok().unwrap()
+    .send_list_req(&mut tx_buff, &mut tx_count, None).err().unwrap();
+  assert_eq!(tx_count, 0);
+  assert_eq!(ftp_transmitter.take_error().unwrap(), FtpError::InvalidState {
+    current: "[state: login-ready]".to_string(),
+    attempted: "send_list_req".to_string(),
+  });
+  assert_eq!(ftp_transmitter.get_wd(), None);
+  assert_eq!(ftp_transmitter.get_type(), None);
+  assert_eq!(ftp_transmitter.get_system(), None);
+  assert_eq!(ftp_transmitter.get_rest_offset(), None);
+  assert_eq!(ftp_transmitter.take_endpoint(), None);
+
+  let _ = ftp_transmitter.send_login(&mut tx_buff, &mut tx_count, "anonymous").ok().unwrap();
+  assert_eq!(str::from_utf8(&tx_buff[0 .. tx_count]).unwrap(), "USER anonymous\r\n");
+}
+
+#[test]
+fn password_after_login() {
+  let mut tx_buff:[u8; 1024] = [0; 1024];
+  let mut tx_count = 0;
+
+  let mut ftp_transmitter = FtpReceiver::new()
+    .try_advance("220 Service ready for new user.\r\n".as_bytes()).ok().unwrap()
+    .send_login(&mut tx_buff, &mut tx_count, "anonymous").ok().unwrap()
+    .try_advance("331 Please specify the password.\r\n".as_bytes()).ok().unwrap()
+    .send_password(&mut tx_buff, &mut tx_count, "anonymous@nowhere.com").ok().unwrap()
+    .try_advance("230 Login successful.\r\n".as_bytes()).ok().unwrap()
+    .send_password(&mut tx_buff, &mut tx_count, "anonymous@nowhere.com").err().unwrap();
+  assert_eq!(format!("{}", ftp_transmitter.take_error().unwrap()),
+             "[send_password is not allowed from the [state: authorized]]");
+  assert_eq!(ftp_transmitter.take_error(), None);
+}
@@ -0,0 +1,47 @@
+extern crate protocol_ftp_client;
+
+use protocol_ftp_client::*;
+use std::str;
+
+#[test]
+fn small_buffer() {
+  let mut tx_buff:[u8; 16] = [0; 16];
+  let mut tx_count = 0;
+
+  let mut ftp_transmitter = FtpReceiver::new()
+    .try_advance("220 Service ready for new user.\r\n".as_bytes()).ok().unwrap()
+    .send_login(&mut tx_buff, &mut tx_count, "a-very-long-user-name").err().unwrap();
+  assert_eq!(ftp_transmitter.take_error().unwrap(), FtpError::BufferTooSmall { needed: 28 });
+  assert_eq!(tx_count, 0);
+
+  let mut big_buff:[u8; 28] = [0; 28];
+  let _ = ftp_transmitter.send_login(&mut big_buff, &mut tx_count, "a-very-long-user-name").ok().unwrap();
+  assert_eq!(str::from_utf8(&big_buff[0 .. tx_count]).unwrap(), "USER a-very-long-user-name\r\n");
+}
+
+#[test]
+fn empty_login() {
+  let mut tx_buff:[u8; 16] = [0; 16];
+  let mut tx_count = 0;
+
+  let _ = FtpReceiver::new()
+    .try_advance("220 Service ready for new user.\r\n".as_bytes()).ok().unwrap()
+    .send_login(&mut tx_buff, &mut tx_count, "").ok().unwrap();
+  assert_eq!(str::from_utf8(&tx_buff[0 .. tx_count]).unwrap(), "USER \r\n");
+}
+
+#[test]
+fn garbage_passive_reply() {
+  let mut tx_buff:[u8; 64] = [0; 64];
+  let mut tx_count = 0;
+
+  let mut ftp_reciver = FtpReceiver::new()
+    .try_advance("220 Service ready for new user.\r\n".as_bytes()).ok().unwrap()
+    .send_login(&mut tx_buff, &mut tx_count, "anonymous").ok().unwrap()
+    .try_advance("331 Please specify the password.\r\n".as_bytes()).ok().unwrap()
+    .send_password(&mut tx_buff, &mut tx_count, "anonymous@nowhere.com").ok().unwrap()
+    .try_advance("230 Login successful.\r\n".as_bytes()).ok().unwrap()
+    .send_pasv_req(&mut tx_buff, &mut tx_count).ok().unwrap()
+    .try_advance("227 Entering Passive Mode (777,88,40,106,195,70).\r\n".as_bytes()).err().unwrap();
+  assert_eq!(ftp_reciver.take_error().unwrap(), FtpError::GarbageData);
+}
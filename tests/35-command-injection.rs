@@ -0,0 +1,46 @@
+extern crate protocol_ftp_client;
+
+mod common;
+
+use protocol_ftp_client::*;
+use common::logged_in;
+use std::str;
+
+#[test]
+fn injected_path() {
+  let mut tx_buff:[u8; 1024] = [0; 1024];
+  let mut tx_count = 0;
+
+  let mut ftp_transmitter = logged_in(&mut tx_buff, &mut tx_count);
+  tx_count = 0;
+
+  for path in vec!["file.txt\r\nDELE x", "file.txt\nDELE x", "file\0.txt"] {
+    ftp_transmitter = ftp_transmitter.send_get_req(&mut tx_buff, &mut tx_count, path).err().unwrap();
+    assert_eq!(ftp_transmitter.take_error().unwrap(), FtpError::InvalidArgument);
+    ftp_transmitter = ftp_transmitter.send_put_req(&mut tx_buff, &mut tx_count, path).err().unwrap();
+    assert_eq!(ftp_transmitter.take_error().unwrap(), FtpError::InvalidArgument);
+    ftp_transmitter = ftp_transmitter.send_cwd_req(&mut tx_buff, &mut tx_count, path).err().unwrap();
+    assert_eq!(ftp_transmitter.take_error().unwrap(), FtpError::InvalidArgument);
+  }
+  assert_eq!(tx_count, 0);
+
+  let _ = ftp_transmitter.send_get_req(&mut tx_buff, &mut tx_count, "file.txt").ok().unwrap();
+  assert_eq!(str::from_utf8(&tx_buff[0 .. tx_count]).unwrap(), "RETR file.txt\r\n");
+}
+
+#[test]
+fn injected_credentials() {
+  let mut tx_buff:[u8; 1024] = [0; 1024];
+  let mut tx_count = 0;
+
+  let mut ftp_transmitter = FtpReceiver::new()
+    .try_advance("220 Service ready for new user.\r\n".as_bytes()).ok().unwrap()
+    .send_login(&mut tx_buff, &mut tx_count, "anonymous\r\nSITE EXEC").err().unwrap();
+  assert_eq!(ftp_transmitter.take_error().unwrap(), FtpError::InvalidArgument);
+
+  let mut ftp_transmitter = ftp_transmitter
+    .send_login(&mut tx_buff, &mut tx_count, "anonymous").ok().unwrap()
+    .try_advance("331 Please specify the password.\r\n".as_bytes()).ok().unwrap()
+    .send_password(&mut tx_buff, &mut tx_count, "secret\r").err().unwrap();
+  assert_eq!(ftp_transmitter.take_error().unwrap(), FtpError::InvalidArgument);
+}
@@ -0,0 +1,79 @@
+extern crate protocol_ftp_client;
+
+mod common;
+
+use protocol_ftp_client::*;
+use common::{files, logged_in};
+
+#[test]
+fn unix_list_sample() {
+  let mut tx_buff:[u8; 1024] = [0; 1024];
+  let mut tx_count = 0;
+
+  let ftp_transmitter = logged_in(&mut tx_buff, &mut tx_count);
+  let listing = "total 24\r
+-rw-r--r--    1 www-data www-data     1830 Jul 19  2014 my  file.txt\r
+lrwxrwxrwx    1 root     root           11 Nov 02 15:30 latest -> my  file.txt\r
+drwxr-xr-x+  12 ftp      ftp          4096 Jan  1 00:00 pub\r
+crw-rw-rw-    1 root     tty        5,   0 Dec 31  2015 tty\r
+brw-rw----    1 root     disk       8,1 Dec 31  2015 sda1\r
+prw-r--r--    1 ftp                      0 Mar  3  2016 fifo\r
+srwxrwxrwx    1 ftp      ftp             0 Mar  3  2016 socket\r\n";
+
+  let list = files(ftp_transmitter.parse_list(listing.as_bytes()));
+  assert_eq!(list.len(), 7);
+
+  assert_eq!(list[0].kind, RemoteFileKind::File);
+  assert_eq!(list[0].name, "my  file.txt");
+  assert_eq!(list[0].size, 1830);
+  assert_eq!(list[0].permissions, Permissions::parse("rw-r--r--"));
+  assert_eq!(list[0].permissions.unwrap().mode(), 0o644);
+  assert_eq!(list[0].links, Some(1));
+  assert_eq!(list[0].owner, Some("www-data".to_string()));
+  assert_eq!(list[0].group, Some("www-data".to_string()));
+  assert_eq!(list[0].date, Some("Jul 19  2014".to_string()));
+
+  assert_eq!(list[1].kind, RemoteFileKind::Symlink { target: "my  file.txt".to_string() });
+  assert_eq!(list[1].name, "latest");
+  assert_eq!(list[1].date, Some("Nov 02 15:30".to_string()));
+
+  assert_eq!(list[2].kind, RemoteFileKind::Directory);
+  assert_eq!(list[2].name, "pub");
+  assert_eq!(list[2].links, Some(12));
+
+  assert_eq!((&list[3].kind, list[3].size, list[3].name.as_str()), (&RemoteFileKind::CharDevice, 0, "tty"));
+  assert_eq!(list[3].group, Some("tty".to_string()));
+  assert_eq!((&list[4].kind, list[4].name.as_str()), (&RemoteFileKind::BlockDevice, "sda1"));
+  assert_eq!(list[4].group, Some("disk".to_string()));
+
+  assert_eq!((&list[5].kind, list[5].name.as_str()), (&RemoteFileKind::Pipe, "fifo"));
+  assert_eq!(list[5].group, None);
+  assert_eq!((&list[6].kind, list[6].name.as_str()), (&RemoteFileKind::Socket, "socket"));
+}
+
+#[test]
+fn huge_sizes_and_permissions() {
+  let mut tx_buff:[u8; 1024] = [0; 1024];
+  let mut tx_count = 0;
+
+  let ftp_transmitter = logged_in(&mut tx_buff, &mut tx_count);
+  let listing = "-rwsr-x--T    1 ftp      ftp   17179869184 Jul 19  2014 huge.img\r
+drwxrwxrwt    2 root     root          4096 Jul 19  2014 tmp\r\n";
+  let list = files(ftp_transmitter.parse_list(listing.as_bytes()));
+  assert_eq!(list[0].size, 17179869184);
+
+  let permissions = list[0].permissions.unwrap();
+  assert_eq!(permissions.user, Access { read: true, write: true, execute: true });
+  assert_eq!(permissions.group, Access { read: true, write: false, execute: true });
+  assert_eq!(permissions.other, Access { read: false, write: false, execute: false });
+  assert!(permissions.setuid && !permissions.setgid && permissions.sticky);
+  assert_eq!(permissions.mode(), 0o5750);
+  assert_eq!(format!("{}", permissions), "rwsr-x--T");
+  assert_eq!(format!("{}", list[1].permissions.unwrap()), "rwxrwxrwt");
+  assert_eq!(Permissions::from_mode(0o1777), list[1].permissions.unwrap());
+
+  let too_huge = "-rw-r--r--    1 ftp      ftp   99999999999999999999 Jul 19  2014 huge.img";
+  assert_eq!(ftp_transmitter.parse_list(format!("{}\r\n", too_huge).as_bytes()),
+             vec![Err(FtpError::UnparseableLine(too_huge.to_string()))]);
+  assert_eq!(Permissions::parse("rwxrwxrwz"), None);
+}
@@ -0,0 +1,50 @@
+extern crate protocol_ftp_client;
+
+mod common;
+
+use protocol_ftp_client::*;
+use common::{files, logged_in};
+
+#[test]
+fn iis_list_sample() {
+  let mut tx_buff:[u8; 1024] = [0; 1024];
+  let mut tx_count = 0;
+
+  let ftp_transmitter = logged_in(&mut tx_buff, &mut tx_count)
+    .send_system_req(&mut tx_buff, &mut tx_count).ok().unwrap()
+    .try_advance("215 Windows_NT\r\n".as_bytes()).ok().unwrap();
+  assert_eq!(ftp_transmitter.get_system(), Some((&"Windows_NT".to_string(), &"".to_string())));
+
+  let listing = "10-18-26  03:14PM       <DIR>          folder\r
+07-19-14  10:11AM                 1830 my  file.txt\r
+01-02-2016  15:30              4294967 archive.zip\r\n";
+  let list = files(ftp_transmitter.parse_list(listing.as_bytes()));
+  assert_eq!(list.len(), 3);
+
+  assert_eq!((&list[0].kind, list[0].size, list[0].name.as_str()), (&RemoteFileKind::Directory, 0, "folder"));
+  assert_eq!(list[0].date, Some("10-18-26  03:14PM".to_string()));
+  assert_eq!(list[0].owner, None);
+  assert_eq!((&list[1].kind, list[1].size, list[1].name.as_str()), (&RemoteFileKind::File, 1830, "my  file.txt"));
+  assert_eq!((&list[2].kind, list[2].size, list[2].name.as_str()), (&RemoteFileKind::File, 4294967, "archive.zip"));
+  assert_eq!(list[2].date, Some("01-02-2016  15:30".to_string()));
+}
+
+#[test]
+fn detected_per_line() {
+  let mut tx_buff:[u8; 1024] = [0; 1024];
+  let mut tx_count = 0;
+
+  // IIS might be configured to Unix-style listings, and unknown servers might send DOS-style ones
+  let listing = "-rw-r--r--    1 owner    group        1830 Jul 19  2014 unix.txt\r
+07-19-14  10:11AM                 1830 dos.txt\r\n";
+
+  let ftp_transmitter = logged_in(&mut tx_buff, &mut tx_count);
+  let list = files(ftp_transmitter.parse_list(listing.as_bytes()));
+  assert_eq!(list.iter().map(|file| file.name.as_str()).collect::<Vec<_>>(), vec!["unix.txt", "dos.txt"]);
+
+  let ftp_transmitter = ftp_transmitter
+    .send_system_req(&mut tx_buff, &mut tx_count).ok().unwrap()
+    .try_advance("215 Windows_NT\r\n".as_bytes()).ok().unwrap();
+  let list = files(ftp_transmitter.parse_list(listing.as_bytes()));
+  assert_eq!(list.iter().map(|file| file.name.as_str()).collect::<Vec<_>>(), vec!["unix.txt", "dos.txt"]);
+}
@@ -0,0 +1,127 @@
+extern crate protocol_ftp_client;
+
+mod common;
+
+use protocol_ftp_client::*;
+use common::{files, logged_in};
+
+#[test]
+fn eplf_list() {
+  let mut tx_buff:[u8; 1024] = [0; 1024];
+  let mut tx_count = 0;
+
+  let ftp_transmitter = logged_in(&mut tx_buff, &mut tx_count);
+  let listing = "+i8388621.48594,m825718503,r,s280,\tdjb.html\r\n+i8388621.50690,m824255907,/,\t514\r\n+up644,r,s10,\tmy file\r\n";
+  let list = files(ftp_transmitter.parse_list(listing.as_bytes()));
+  assert_eq!(list.len(), 3);
+
+  assert_eq!((&list[0].kind, list[0].size, list[0].name.as_str()), (&RemoteFileKind::File, 280, "djb.html"));
+  assert_eq!(list[0].unique, Some("8388621.48594".to_string()));
+  assert_eq!(format!("{}", list[0].modified.unwrap()), "1996-03-01T22:15:03.000Z");
+  assert_eq!((&list[1].kind, list[1].name.as_str()), (&RemoteFileKind::Directory, "514"));
+  assert_eq!((list[2].unix_mode, list[2].name.as_str()), (Some(0o644), "my file"));
+  assert_eq!(list[2].permissions, Some(Permissions::from_mode(0o644)));
+}
+
+#[test]
+fn vms_list() {
+  let mut tx_buff:[u8; 1024] = [0; 1024];
+  let mut tx_count = 0;
+
+  let ftp_transmitter = logged_in(&mut tx_buff, &mut tx_count);
+  let listing = "Directory DISK$ANON:[ANONYMOUS]\r
+\r
+CII-MANUAL.TEX;1  213/216  29-JAN-1996 03:33:12  [ANONYMOUS,ANONYMOUS]   (RWED,RWED,,)\r
+PUB.DIR;1           1/3    18-OCT-2026 15:14  [SYSTEM]  (RWE,RWE,RE,RE)\r
+\r
+Total of 2 files, 214/219 blocks.\r\n";
+  let list = files(ftp_transmitter.parse_list(listing.as_bytes()));
+  assert_eq!(list.len(), 2);
+
+  assert_eq!((&list[0].kind, list[0].size, list[0].name.as_str()), (&RemoteFileKind::File, 213 * 512, "CII-MANUAL.TEX"));
+  assert_eq!((&list[0].owner, &list[0].group), (&Some("ANONYMOUS".to_string()), &Some("ANONYMOUS".to_string())));
+  assert_eq!(list[0].permissions.unwrap().mode(), 0o700);
+  assert_eq!(list[0].date, Some("29-JAN-1996 03:33:12".to_string()));
+  assert_eq!((&list[1].kind, list[1].name.as_str()), (&RemoteFileKind::Directory, "PUB"));
+  assert_eq!(list[1].permissions.unwrap().mode(), 0o755);
+  assert_eq!((&list[1].owner, &list[1].group), (&Some("SYSTEM".to_string()), &None));
+}
+
+#[test]
+fn mvs_list() {
+  let mut tx_buff:[u8; 1024] = [0; 1024];
+  let mut tx_count = 0;
+
+  let ftp_transmitter = logged_in(&mut tx_buff, &mut tx_count);
+  let listing = "Volume Unit    Referred Ext Used Recfm Lrecl BlkSz Dsorg Dsname\r
+WYNK01 3390   2026/10/18  1   15  FB      80  3120  PO  USER.CNTL\r
+WYNK02 3390   **NONE**    1    1  VB     255 27998  PS  USER.LOG\r
+Migrated                                                USER.OLD.DATA\r
+Pseudo Directory                                        USER.SUB\r\n";
+  let list = files(ftp_transmitter.parse_list(listing.as_bytes()));
+  assert_eq!(list.len(), 4);
+
+  assert_eq!((&list[0].kind, list[0].name.as_str()), (&RemoteFileKind::Directory, "USER.CNTL"));
+  assert_eq!(list[0].date, Some("2026/10/18".to_string()));
+  assert_eq!((&list[1].kind, list[1].name.as_str(), &list[1].date), (&RemoteFileKind::File, "USER.LOG", &None));
+  assert_eq!((&list[2].kind, list[2].name.as_str()), (&RemoteFileKind::File, "USER.OLD.DATA"));
+  assert_eq!((&list[3].kind, list[3].name.as_str()), (&RemoteFileKind::Directory, "USER.SUB"));
+}
+
+#[test]
+fn eplf_time_out_of_range() {
+  let mut tx_buff:[u8; 1024] = [0; 1024];
+  let mut tx_count = 0;
+
+  let ftp_transmitter = logged_in(&mut tx_buff, &mut tx_count);
+  for line in ["+m-9223372036854775808,\tx", "+m9223372036854775807,\tx", "+m-62167219201,\tx"].iter() {
+    assert_eq!(ftp_transmitter.parse_list(format!("{}\r\n", line).as_bytes()), vec![Err(FtpError::UnparseableLine(line.to_string()))]);
+  }
+  let list = files(ftp_transmitter.parse_list("+m-62167219200,\tx\r\n".as_bytes()));
+  assert_eq!(format!("{}", list[0].modified.unwrap()), "0000-01-01T00:00:00.000Z");
+}
+
+#[test]
+fn unparseable_line() {
+  let mut tx_buff:[u8; 1024] = [0; 1024];
+  let mut tx_count = 0;
+
+  let ftp_transmitter = logged_in(&mut tx_buff, &mut tx_count);
+  let listing = "-rw-r--r--    1 ftp      ftp          5430 Jul 19  2014 favicon.ico\r
+something strange\r
+drwxr-xr-x    3 ftp      ftp             3 Jul 19  2014 pub\r\n";
+  let list = ftp_transmitter.parse_list(listing.as_bytes());
+  assert_eq!(list.len(), 3);
+  assert_eq!(list[0].as_ref().map(|file| file.name.as_str()), Ok("favicon.ico"));
+  assert_eq!(list[1], Err(FtpError::UnparseableLine("something strange".to_string())));
+  assert_eq!(list[2].as_ref().map(|file| file.name.as_str()), Ok("pub"));
+}
+
+struct NameOnlyParser;
+
+impl ListParser for NameOnlyParser {
+  fn parse_line(&self, line: &str) -> Option<RemoteFile> {
+    if line.starts_with("name: ") {
+      Some(RemoteFile { name: line[6 ..].to_string(), ..RemoteFile::default() })
+    } else {
+      None
+    }
+  }
+
+  fn is_header(&self, line: &str) -> bool {
+    line == "names:"
+  }
+}
+
+#[test]
+fn custom_parser() {
+  let mut tx_buff:[u8; 1024] = [0; 1024];
+  let mut tx_count = 0;
+
+  let mut ftp_transmitter = logged_in(&mut tx_buff, &mut tx_count);
+  ftp_transmitter.add_list_parser(Box::new(NameOnlyParser));
+
+  let listing = "names:\r\nname: a.txt\r\n-rw-r--r--    1 ftp      ftp          5430 Jul 19  2014 b.txt\r\n";
+  let list = files(ftp_transmitter.parse_list(listing.as_bytes()));
+  assert_eq!(list.iter().map(|file| file.name.as_str()).collect::<Vec<_>>(), vec!["a.txt", "b.txt"]);
+}
@@ -0,0 +1,88 @@
+extern crate protocol_ftp_client;
+
+mod common;
+
+use protocol_ftp_client::*;
+use common::{files, logged_in};
+use std::time::{Duration, UNIX_EPOCH};
+
+fn modified(file: &RemoteFile) -> String {
+  format!("{}", file.modified.unwrap())
+}
+
+#[test]
+fn unix_dates() {
+  let mut tx_buff:[u8; 1024] = [0; 1024];
+  let mut tx_count = 0;
+
+  let ftp_transmitter = logged_in(&mut tx_buff, &mut tx_count);
+  let listing = "-rw-r--r--    1 ftp      ftp          5430 Jul 19  2014 favicon.ico\r
+-rw-r--r--    1 ftp      ftp           660 Oct 18 15:30 today.html\r
+-rw-r--r--    1 ftp      ftp           660 Oct 19 01:00 tomorrow.html\r
+drwxr-xr-x    3 ftp      ftp             3 Nov 02 15:30 pub\r\n";
+  let now = Timestamp::parse_time_val("20261018120000").unwrap();
+  let list = files(ftp_transmitter.parse_list_at(listing.as_bytes(), &now));
+
+  assert_eq!(modified(&list[0]), "2014-07-19T00:00:00.000Z");
+  assert_eq!(modified(&list[1]), "2026-10-18T15:30:00.000Z");
+  // servers might be ahead due to time zone
+  assert_eq!(modified(&list[2]), "2026-10-19T01:00:00.000Z");
+  assert_eq!(modified(&list[3]), "2025-11-02T15:30:00.000Z");
+}
+
+#[test]
+fn year_boundary() {
+  let now = Timestamp::parse_time_val("20260101003000").unwrap();
+  assert_eq!(format!("{}", Timestamp::parse_list_date("Dec 31 23:59", &now).unwrap()), "2025-12-31T23:59:00.000Z");
+  assert_eq!(format!("{}", Timestamp::parse_list_date("Jan  1 00:10", &now).unwrap()), "2026-01-01T00:10:00.000Z");
+  assert_eq!(Timestamp::parse_list_date("Foo  1 00:10", &now), None);
+  assert_eq!(Timestamp::parse_list_date("Jan 32  2014", &now), None);
+
+  let first_year = Timestamp::parse_time_val("00000101000000").unwrap();
+  assert_eq!(Timestamp::parse_list_date("Dec 31 10:00", &first_year), None);
+  // Feb 29 of the previous, non-leap year
+  let leap_year = Timestamp::parse_time_val("20240101000000").unwrap();
+  assert_eq!(Timestamp::parse_list_date("Feb 29 10:00", &leap_year), None);
+}
+
+#[test]
+fn other_formats() {
+  let now = Timestamp::parse_time_val("20261018120000").unwrap();
+  let parse = |date| format!("{}", Timestamp::parse_list_date(date, &now).unwrap());
+
+  assert_eq!(parse("10-18-26  03:14PM"), "2026-10-18T15:14:00.000Z");
+  assert_eq!(parse("07-19-98  12:11AM"), "1998-07-19T00:11:00.000Z");
+  assert_eq!(parse("01-02-2016  15:30"), "2016-01-02T15:30:00.000Z");
+  assert_eq!(parse("29-JAN-1996 03:33:12"), "1996-01-29T03:33:12.000Z");
+  assert_eq!(parse("2026/10/18"), "2026-10-18T00:00:00.000Z");
+  assert_eq!(Timestamp::parse_list_date("10-18-26  13:14PM", &now), None);
+}
+
+#[test]
+fn non_ascii_digits() {
+  let mut tx_buff:[u8; 1024] = [0; 1024];
+  let mut tx_count = 0;
+
+  let now = Timestamp::parse_time_val("20261018120000").unwrap();
+  assert_eq!(Timestamp::parse_list_date("Jul ١٩  2014", &now), None);
+  assert_eq!(Timestamp::parse_list_date("Jul 19  ٢٠١٤", &now), None);
+  assert_eq!(Timestamp::parse_list_date("١٠-18-26  03:14PM", &now), None);
+  assert_eq!(Timestamp::parse_list_date("29-JAN-1996 03:33:١٢", &now), None);
+  assert_eq!(Timestamp::parse_list_date("2026/١٠/18", &now), None);
+
+  let listing = "-rw-r--r--    1 ftp      ftp          5430 Jul ١٩  2014 favicon.ico\r
+١٠-18-26  03:14PM       <DIR>          folder\r\n";
+  let list = logged_in(&mut tx_buff, &mut tx_count).parse_list_at(listing.as_bytes(), &now);
+  assert_eq!(list.len(), 2);
+  assert!(list.iter().all(|file| file.is_err()));
+}
+
+#[test]
+fn from_system_time() {
+  let time = UNIX_EPOCH + Duration::from_millis(1405728000250);
+  assert_eq!(format!("{}", Timestamp::from_system_time(time)), "2014-07-19T00:00:00.250Z");
+  assert_eq!(Timestamp::from_system_time(time).to_system_time(), time);
+
+  let before_epoch = UNIX_EPOCH - Duration::from_millis(1500);
+  assert_eq!(format!("{}", Timestamp::from_system_time(before_epoch)), "1969-12-31T23:59:58.500Z");
+}
@@ -0,0 +1,70 @@
+extern crate protocol_ftp_client;
+
+mod common;
+
+use protocol_ftp_client::*;
+use common::logged_in;
+
+#[test]
+fn fed_by_bytes() {
+  let mut tx_buff:[u8; 1024] = [0; 1024];
+  let mut tx_count = 0;
+
+  let ftp_transmitter = logged_in(&mut tx_buff, &mut tx_count);
+  let listing = "total 8\r
+-rw-r--r--    1 ftp      ftp          5430 Jul 19  2014 favicon.ico\r
+-rw-r--r--    1 ftp      ftp           660 Nov 02 15:30 привет.html\r
+drwxr-xr-x    3 ftp      ftp             3 Jul 19  2014 pub";
+  let now = Timestamp::parse_time_val("20261018120000").unwrap();
+
+  let mut stream = ftp_transmitter.list_stream_at(&now);
+  let mut names = Vec::new();
+  for byte in listing.as_bytes() {
+    stream.feed(&[*byte]);
+    for file in stream.files() {
+      names.push(file.unwrap().name);
+    }
+  }
+  assert_eq!(names, vec!["favicon.ico", "привет.html"]);
+
+  stream.finish();
+  let last = stream.next_file().unwrap().unwrap();
+  assert_eq!((&last.kind, last.name.as_str()), (&RemoteFileKind::Directory, "pub"));
+  assert_eq!(stream.next_file(), None);
+}
+
+#[test]
+fn errors_do_not_stop_stream() {
+  let mut tx_buff:[u8; 1024] = [0; 1024];
+  let mut tx_count = 0;
+
+  let ftp_transmitter = logged_in(&mut tx_buff, &mut tx_count);
+  let mut stream = ftp_transmitter.list_stream();
+  stream.feed("something strange\r\n".as_bytes());
+  stream.feed(&[0xff, b'\r', b'\n']);
+  stream.feed("-rw-r--r--    1 ftp      ftp          5430 Jul 19  2014 favicon.ico\r\n".as_bytes());
+
+  assert_eq!(stream.next_file(), Some(Err(FtpError::UnparseableLine("something strange".to_string()))));
+  assert_eq!(stream.next_file(), Some(Err(FtpError::UnparseableLine("\u{FFFD}".to_string()))));
+  assert_eq!(stream.next_file().unwrap().unwrap().name, "favicon.ico");
+  assert_eq!(stream.next_file(), None);
+}
+
+#[test]
+fn files_iterator() {
+  let mut tx_buff:[u8; 1024] = [0; 1024];
+  let mut tx_count = 0;
+
+  let ftp_transmitter = logged_in(&mut tx_buff, &mut tx_count);
+  let mut stream = ftp_transmitter.list_stream();
+  stream.feed("-rw-r--r--    1 ftp      ftp          5430 Jul 19  2014 favicon.ico\r\nsomething strange\r\n".as_bytes());
+  stream.feed("drwxr-xr-x    3 ftp      ftp             3 Jul 19  2014 pub\r\n-rw-r--r--    1 ftp".as_bytes());
+
+  let names: Vec<String> = stream.files().filter_map(|file| file.ok()).map(|file| file.name).collect();
+  assert_eq!(names, vec!["favicon.ico", "pub"]);
+  assert_eq!(stream.files().count(), 0);
+
+  stream.feed("      ftp           660 Nov 02 15:30 index.html".as_bytes());
+  stream.finish();
+  assert_eq!(stream.files().map(|file| file.unwrap().size).collect::<Vec<_>>(), vec![660]);
+}
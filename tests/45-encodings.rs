@@ -0,0 +1,126 @@
+extern crate protocol_ftp_client;
+
+mod common;
+
+use protocol_ftp_client::*;
+use common::files;
+use std::str;
+
+fn logged_in(tx_buff: &mut [u8], tx_count: &mut usize, encoding: Encoding) -> FtpTransmitter {
+  let mut ftp_receiver = FtpReceiver::new();
+  ftp_receiver.set_encoding(encoding);
+  common::logged_in_from(ftp_receiver, tx_buff, tx_count)
+}
+
+#[test]
+fn encode_decode() {
+  assert_eq!(Encoding::Cp1251.encode("Привет, Ёж №1"), Some(vec![0xCF, 0xF0, 0xE8, 0xE2, 0xE5, 0xF2, 0x2C, 0x20, 0xA8, 0xE6, 0x20, 0xB9, 0x31]));
+  assert_eq!(Encoding::Cp1251.decode(&[0xCF, 0xF0, 0xE8, 0xE2, 0xE5, 0xF2, 0xFF]), Some("Приветя".to_string()));
+  assert_eq!(Encoding::Cp1251.encode("日本"), None);
+  assert_eq!(Encoding::Latin1.encode("café"), Some(vec![0x63, 0x61, 0x66, 0xE9]));
+  assert_eq!(Encoding::Latin1.decode(&[0x63, 0x61, 0x66, 0xE9]), Some("café".to_string()));
+  assert_eq!(Encoding::Latin1.encode("Привет"), None);
+  assert_eq!(Encoding::Utf8.decode(&[0x63, 0x61, 0x66, 0xE9]), None);
+
+  // all bytes survive round trip
+  let bytes: Vec<u8> = (0 .. 256).map(|b| b as u8).collect();
+  for encoding in &[Encoding::Latin1, Encoding::Cp1251] {
+    assert_eq!(encoding.encode(&encoding.decode(&bytes).unwrap()), Some(bytes.clone()));
+  }
+}
+
+#[test]
+fn cp1251_session() {
+  let mut tx_buff:[u8; 1024] = [0; 1024];
+  let mut tx_count = 0;
+
+  let ftp_transmitter = logged_in(&mut tx_buff, &mut tx_count, Encoding::Cp1251);
+  assert_eq!(ftp_transmitter.get_encoding(), Encoding::Cp1251);
+
+  let ftp_reciver = ftp_transmitter.send_cwd_req(&mut tx_buff, &mut tx_count, "/Папка").ok().unwrap();
+  assert_eq!(&tx_buff[0 .. tx_count], &[b'C', b'W', b'D', b' ', b'/', 0xCF, 0xE0, 0xEF, 0xEA, 0xE0, b'\r', b'\n']);
+
+  let mut reply = "250 OK, ".as_bytes().to_vec();
+  reply.extend_from_slice(&[0xE3, 0xEE, 0xF2, 0xEE, 0xE2, 0xEE, b'\r', b'\n']);
+  reply.extend_from_slice("257 \"/".as_bytes());
+  reply.extend_from_slice(&[0xCF, 0xE0, 0xEF, 0xEA, 0xE0]);
+  reply.extend_from_slice("\" is the current directory\r\n".as_bytes());
+
+  let ftp_transmitter = ftp_reciver.try_advance(&reply).ok().unwrap();
+  assert_eq!(ftp_transmitter.get_last_reply().unwrap().lines, vec!["OK, готово"]);
+  let ftp_transmitter = ftp_transmitter.send_pwd_req(&mut tx_buff, &mut tx_count).ok().unwrap()
+    .try_advance(&[]).ok().unwrap();
+  assert_eq!(ftp_transmitter.get_wd(), Some("/Папка"));
+
+  let mut listing = "-rw-r--r--    1 ftp      ftp          5430 Jul 19  2014 ".as_bytes().to_vec();
+  listing.extend_from_slice(&[0xD4, 0xE0, 0xE9, 0xEB, b'.', b't', b'x', b't', b'\r', b'\n']);
+  let list = files(ftp_transmitter.parse_list(&listing));
+  assert_eq!(list[0].name, "Файл.txt");
+  assert_eq!(list[0].raw_name, vec![0xD4, 0xE0, 0xE9, 0xEB, b'.', b't', b'x', b't']);
+
+  let mut tx_count = 0;
+  let mut ftp_transmitter = ftp_transmitter.send_dele_req(&mut tx_buff, &mut tx_count, "日本.txt").err().unwrap();
+  assert_eq!(ftp_transmitter.take_error(), Some(FtpError::UnencodableArgument));
+  assert_eq!(tx_count, 0);
+}
+
+#[test]
+fn latin1_listing() {
+  let mut tx_buff:[u8; 1024] = [0; 1024];
+  let mut tx_count = 0;
+
+  let mut listing = "-rw-r--r--    1 ftp      ftp          5430 Jul 19  2014 caf".as_bytes().to_vec();
+  listing.extend_from_slice(&[0xE9, b'\r', b'\n']);
+
+  // undecodable name does not break the listing, and the raw name is kept as is
+  let mut ftp_transmitter = logged_in(&mut tx_buff, &mut tx_count, Encoding::Utf8);
+  let mut two_lines = listing.clone();
+  two_lines.extend_from_slice("lrwxrwxrwx    1 ftp      ftp            11 Jul 19  2014 latest -> caf".as_bytes());
+  two_lines.extend_from_slice(&[0xE9, b'\r', b'\n']);
+  let list = files(ftp_transmitter.parse_list(&two_lines));
+  assert_eq!((list[0].name.as_str(), &list[0].raw_name[..]), ("caf\u{FFFD}", &[b'c', b'a', b'f', 0xE9][..]));
+  assert_eq!((list[1].name.as_str(), &list[1].raw_name[..]), ("latest", &b"latest"[..]));
+  assert_eq!(list[1].kind, RemoteFileKind::Symlink { target: "caf\u{FFFD}".to_string() });
+
+  let mut mlsd = "type=file;size=4; caf".as_bytes().to_vec();
+  mlsd.extend_from_slice(&[0xE9, b'\r', b'\n']);
+  mlsd.extend_from_slice("type=file;size=4; \u{10FF41}".as_bytes());
+  let list = files(ftp_transmitter.parse_mlsd(&mlsd));
+  assert_eq!(&list[0].raw_name[..], &[b'c', b'a', b'f', 0xE9][..]);
+  assert_eq!((list[1].name.as_str(), &list[1].raw_name[..]), ("\u{10FF41}", "\u{10FF41}".as_bytes()));
+
+  let names = ftp_transmitter.parse_nlst(b"good.txt\r\nbad\xe9.txt");
+  assert_eq!(names, vec![
+    RemoteName { name: "good.txt".to_string(), raw_name: b"good.txt".to_vec() },
+    RemoteName { name: "bad\u{FFFD}.txt".to_string(), raw_name: b"bad\xe9.txt".to_vec() },
+  ]);
+
+  ftp_transmitter.set_encoding(Encoding::Latin1);
+  let list = files(ftp_transmitter.parse_list(&listing));
+  assert_eq!((list[0].name.as_str(), &list[0].raw_name[..]), ("café", &[b'c', b'a', b'f', 0xE9][..]));
+  assert_eq!(ftp_transmitter.parse_nlst(&[b'c', b'a', b'f', 0xE9, b'\r', b'\n']),
+             vec![RemoteName { name: "café".to_string(), raw_name: vec![b'c', b'a', b'f', 0xE9] }]);
+}
+
+#[test]
+fn opts_utf8() {
+  let mut tx_buff:[u8; 1024] = [0; 1024];
+  let mut tx_count = 0;
+
+  let ftp_reciver = logged_in(&mut tx_buff, &mut tx_count, Encoding::Latin1)
+    .send_opts_utf8_req(&mut tx_buff, &mut tx_count).ok().unwrap();
+  assert_eq!(str::from_utf8(&tx_buff[0 .. tx_count]).unwrap(), "OPTS UTF8 ON\r\n");
+  let ftp_transmitter = ftp_reciver.try_advance("200 Always in UTF8 mode.\r\n".as_bytes()).ok().unwrap();
+  assert_eq!(ftp_transmitter.get_encoding(), Encoding::Utf8);
+
+  let ftp_transmitter = logged_in(&mut tx_buff, &mut tx_count, Encoding::Latin1)
+    .send_opts_utf8_req(&mut tx_buff, &mut tx_count).ok().unwrap()
+    .try_advance("202 UTF8 mode is always enabled. No need to send this command.\r\n".as_bytes()).ok().unwrap();
+  assert_eq!(ftp_transmitter.get_encoding(), Encoding::Utf8);
+
+  let mut ftp_receiver = logged_in(&mut tx_buff, &mut tx_count, Encoding::Latin1)
+    .send_opts_utf8_req(&mut tx_buff, &mut tx_count).ok().unwrap()
+    .try_advance("501 Option not understood.\r\n".as_bytes()).err().unwrap();
+  assert_eq!(ftp_receiver.take_error(), Some(FtpError::ServerError(501, "Option not understood.".to_string())));
+  assert_eq!(ftp_receiver.to_transmitter().get_encoding(), Encoding::Latin1);
+}
@@ -0,0 +1,116 @@
+extern crate protocol_ftp_client;
+
+use protocol_ftp_client::*;
+use protocol_ftp_client::session::*;
+use std::str;
+
+fn done<S, F>(pending: Pending<S, F>, reply: &str) -> Session<S> {
+  match pending.feed(reply.as_bytes()) {
+    Outcome::Done(session) => session,
+    _ => panic!("unexpected outcome for {}", reply),
+  }
+}
+
+fn rejected<S, F>(pending: Pending<S, F>, reply: &str) -> (Session<F>, FtpError) {
+  match pending.feed(reply.as_bytes()) {
+    Outcome::Rejected(session, error) => (session, error),
+    _ => panic!("unexpected outcome for {}", reply),
+  }
+}
+
+fn logged_in(tx_buff: &mut [u8], tx_count: &mut usize) -> Session<LoggedIn> {
+  let session = done(connect(), "220 Service ready for new user.\r\n");
+  let session = done(session.send_login(tx_buff, tx_count, "user").ok().unwrap(),
+                     "331 User name okay, need password for user.\r\n");
+  done(session.send_password(tx_buff, tx_count, "secret").ok().unwrap(), "230 User logged in, proceed.\r\n")
+}
+
+#[test]
+fn list_session() {
+  let mut tx_buff:[u8; 1024] = [0; 1024];
+  let mut tx_count = 0;
+
+  let mut session = done(logged_in(&mut tx_buff, &mut tx_count).send_pasv_req(&mut tx_buff, &mut tx_count).ok().unwrap(),
+                         "227 Entering Passive Mode (127,0,0,1,4,1).\r\n");
+  assert_eq!(session.transmitter_mut().take_endpoint(), Some("127.0.0.1:1025".parse().unwrap()));
+
+  let pending = session.send_list_req(&mut tx_buff, &mut tx_count, None).ok().unwrap();
+  assert_eq!(str::from_utf8(&tx_buff[0 .. tx_count]).unwrap(), "LIST\r\n");
+
+  let pending = match pending.feed("150 Here comes ".as_bytes()) {
+    Outcome::Pending(pending) => pending,
+    _ => panic!("reply is not complete"),
+  };
+  let session = done(pending, "the directory listing.\r\n");
+  let list = session.transmitter().parse_list("drwxr-xr-x    3 ftp      ftp             3 Nov 02 15:30 pub\r\n".as_bytes());
+  assert_eq!(list[0].as_ref().map(|file| file.name.as_str()), Ok("pub"));
+
+  let session = done(session.finish(), "226 Directory send OK.\r\n");
+  let _ = session.send_pwd_req(&mut tx_buff, &mut tx_count).ok().unwrap();
+  assert_eq!(str::from_utf8(&tx_buff[0 .. tx_count]).unwrap(), "PWD\r\n");
+}
+
+#[test]
+fn resume_and_rename() {
+  let mut tx_buff:[u8; 1024] = [0; 1024];
+  let mut tx_count = 0;
+
+  let session = done(logged_in(&mut tx_buff, &mut tx_count).send_rest_req(&mut tx_buff, &mut tx_count, 1024).ok().unwrap(),
+                     "350 Restarting at 1024. Send STORE or RETRIEVE.\r\n");
+  let session = done(session.send_get_req(&mut tx_buff, &mut tx_count, "build.log").ok().unwrap(),
+                     "150 Opening BINARY mode data connection for build.log.\r\n");
+  let session = done(session.finish(), "226 Transfer complete.\r\n");
+
+  let session = done(session.send_rnfr_req(&mut tx_buff, &mut tx_count, "build.log").ok().unwrap(),
+                     "350 Ready for RNTO.\r\n");
+  let _ = done(session.send_rnto_req(&mut tx_buff, &mut tx_count, "old.log").ok().unwrap(), "250 Rename successful.\r\n");
+  assert_eq!(str::from_utf8(&tx_buff[0 .. tx_count]).unwrap(), "RNTO old.log\r\n");
+}
+
+#[test]
+fn rejected_commands() {
+  let mut tx_buff:[u8; 1024] = [0; 1024];
+  let mut tx_count = 0;
+
+  let (_, error) = rejected(connect(), "421 Service not available, closing control connection.\r\n");
+  assert_eq!(error, FtpError::ServerError(421, "Service not available, closing control connection.".to_string()));
+
+  let session = done(connect(), "220 Service ready for new user.\r\n");
+  let session = done(session.send_login(&mut tx_buff, &mut tx_count, "user").ok().unwrap(),
+                     "331 User name okay, need password for user.\r\n");
+  let (session, error) = rejected(session.send_password(&mut tx_buff, &mut tx_count, "11").ok().unwrap(),
+                                  "530 Authentication failed.\r\n");
+  assert_eq!(error, FtpError::AuthFailed);
+  let _ = session.send_login(&mut tx_buff, &mut tx_count, "anonymous").ok().unwrap();
+
+  let (session, _) = rejected(logged_in(&mut tx_buff, &mut tx_count).send_get_req(&mut tx_buff, &mut tx_count, "missing.txt").ok().unwrap(),
+                              "550 Failed to open file.\r\n");
+  let _ = session.send_cwd_req(&mut tx_buff, &mut tx_count, "/pub").ok().unwrap();
+
+  let mut session = logged_in(&mut tx_buff, &mut tx_count).send_cwd_req(&mut tx_buff, &mut tx_count, "a\r\nDELE b").err().unwrap();
+  assert_eq!(session.take_error(), Some(FtpError::InvalidArgument));
+}
+
+#[test]
+fn login_without_password() {
+  let mut tx_buff:[u8; 1024] = [0; 1024];
+  let mut tx_count = 0;
+
+  let session = done(connect(), "220 Service ready for new user.\r\n");
+  let session = done(session.send_login(&mut tx_buff, &mut tx_count, "anonymous").ok().unwrap(),
+                     "230 Anonymous access granted.\r\n");
+  let _ = session.logged_in().ok().unwrap().send_pwd_req(&mut tx_buff, &mut tx_count).ok().unwrap();
+
+  let session = done(connect(), "220 Service ready for new user.\r\n");
+  let session = done(session.send_login(&mut tx_buff, &mut tx_count, "user").ok().unwrap(),
+                     "331 User name okay, need password for user.\r\n");
+  let _ = session.logged_in().err().unwrap().send_password(&mut tx_buff, &mut tx_count, "secret").ok().unwrap();
+}
+
+#[test]
+fn garbage_reply() {
+  match connect().feed("hello\r\n".as_bytes()) {
+    Outcome::Error(error) => assert_eq!(error, FtpError::GarbageData),
+    _ => panic!("garbage is accepted"),
+  }
+}
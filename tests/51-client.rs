@@ -0,0 +1,154 @@
+extern crate protocol_ftp_client;
+
+use protocol_ftp_client::*;
+use protocol_ftp_client::client::*;
+
+fn transmitted(client: &mut FtpClient) -> String {
+  String::from_utf8(client.poll_transmit().unwrap()).unwrap()
+}
+
+fn logged_in() -> FtpClient {
+  let mut client = FtpClient::new();
+  client.handle_input("220 Service ready for new user.\r\n".as_bytes());
+  assert_eq!(client.poll_event(), Some(Event::Reply(Reply { code: 220, lines: vec!["Service ready for new user.".to_string()] })));
+
+  client.login("user", "secret").unwrap();
+  assert_eq!(transmitted(&mut client), "USER user\r\n");
+  client.handle_input("331 User name okay, need password for user.\r\n".as_bytes());
+  assert_eq!(client.poll_event(), None);
+  assert_eq!(transmitted(&mut client), "PASS secret\r\n");
+  client.handle_input("230 User logged in, proceed.\r\n".as_bytes());
+  assert_eq!(client.poll_event(), Some(Event::LoggedIn));
+  client
+}
+
+#[test]
+fn list_sample() {
+  let mut client = logged_in();
+  client.send_pasv_req().unwrap();
+  assert_eq!(transmitted(&mut client), "PASV\r\n");
+  client.handle_input("227 Entering Passive Mode (127,0,0,1,4,1).\r\n".as_bytes());
+  assert_eq!(client.poll_event(), Some(Event::EndpointReady("127.0.0.1:1025".parse().unwrap())));
+
+  client.send_list_req(None).unwrap();
+  assert_eq!(transmitted(&mut client), "LIST\r\n");
+  client.handle_input("150 Here comes the directory listing.\r\n226 Dir".as_bytes());
+  assert_eq!(client.poll_event().map(|event| match event { Event::Reply(reply) => reply.code, _ => 0 }), Some(150));
+  assert_eq!(client.poll_event(), None);
+  assert!(!client.is_idle());
+
+  client.handle_input("ectory send OK.\r\n".as_bytes());
+  assert_eq!(client.poll_event(), Some(Event::TransferComplete));
+  assert!(client.is_idle());
+
+  let list = client.transmitter().unwrap()
+    .parse_list("drwxr-xr-x    3 ftp      ftp             3 Nov 02 15:30 pub\r\n".as_bytes());
+  assert_eq!(list[0].as_ref().map(|file| file.name.as_str()), Ok("pub"));
+
+  client.send_pwd_req().unwrap();
+  client.handle_input("257 \"/\" is the current directory\r\n".as_bytes());
+  assert_eq!(client.transmitter().unwrap().get_wd(), Some("/"));
+}
+
+#[test]
+fn one_command_at_time() {
+  let mut client = FtpClient::new();
+  assert_eq!(client.send_pwd_req(), Err(FtpError::InvalidState { current: "[state: non-authorized]".to_string(), attempted: "send_pwd_req".to_string() }));
+  assert_eq!(client.poll_transmit(), None);
+
+  let mut client = logged_in();
+  client.send_cwd_req("/pub").unwrap();
+  assert!(client.send_pwd_req().is_err());
+  assert!(client.transmitter().is_none());
+  client.handle_input("250 Directory successfully changed.\r\n".as_bytes());
+  client.send_pwd_req().unwrap();
+  assert_eq!(transmitted(&mut client), "CWD /pub\r\n");
+  assert_eq!(transmitted(&mut client), "PWD\r\n");
+}
+
+#[test]
+fn rejected_commands() {
+  let mut client = FtpClient::new();
+  client.handle_input("220 Service ready for new user.\r\n".as_bytes());
+  client.login("user", "11").unwrap();
+  client.handle_input("331 User name okay, need password for user.\r\n530 Authentication failed.\r\n".as_bytes());
+  assert_eq!(client.poll_event(), Some(Event::Reply(Reply { code: 220, lines: vec!["Service ready for new user.".to_string()] })));
+  assert_eq!(client.poll_event(), Some(Event::Error(FtpError::AuthFailed)));
+  client.login("anonymous", "anonymous@nowhere.com").unwrap();
+
+  let mut client = logged_in();
+  client.send_get_req("missing.txt").unwrap();
+  client.handle_input("550 Failed to open file.\r\n".as_bytes());
+  assert_eq!(client.poll_event(), Some(Event::Error(FtpError::ServerError(550, "Failed to open file.".to_string()))));
+  assert!(client.is_idle());
+
+  assert_eq!(client.send_dele_req("a\r\nRMD b"), Err(FtpError::InvalidArgument));
+  assert!(client.is_idle());
+}
+
+#[test]
+fn long_command() {
+  let mut client = logged_in();
+  let path = format!("/{}", "a".repeat(5000));
+  client.send_mkd_req(&path).unwrap();
+  assert_eq!(transmitted(&mut client), format!("MKD {}\r\n", path));
+}
+
+#[test]
+fn garbage_closes_client() {
+  let mut client = FtpClient::new();
+  client.handle_input("hello\r\n".as_bytes());
+  assert_eq!(client.poll_event(), Some(Event::Error(FtpError::GarbageData)));
+  assert!(client.is_closed());
+  assert!(client.login("user", "secret").is_err());
+
+  client.handle_input("220 Service ready for new user.\r\n".as_bytes());
+  assert_eq!(client.poll_event(), None);
+}
+
+#[test]
+fn login_without_password() {
+  let mut client = FtpClient::new();
+  client.handle_input("220 Service ready for new user.\r\n".as_bytes());
+  client.login("anonymous", "anonymous@nowhere.com").unwrap();
+  client.handle_input("230 Anonymous access granted.\r\n".as_bytes());
+
+  assert!(client.poll_event().is_some());
+  assert_eq!(client.poll_event(), Some(Event::LoggedIn));
+  assert_eq!(transmitted(&mut client), "USER anonymous\r\n");
+  assert_eq!(client.poll_transmit(), None);
+  assert!(client.is_idle());
+  client.send_pwd_req().unwrap();
+}
+
+#[test]
+fn unsolicited_reply() {
+  let mut client = logged_in();
+  client.handle_input("421 Timeout, closing control connection.\r\n".as_bytes());
+  assert_eq!(client.poll_event(), Some(Event::Error(FtpError::ServerError(421, "Timeout, closing control connection.".to_string()))));
+
+  let mut client = logged_in();
+  client.send_cwd_req("/pub").unwrap();
+  client.handle_input("250 Directory successfully changed.\r\n421 Timeout.\r\n".as_bytes());
+  assert_eq!(client.poll_event(), Some(Event::Reply(Reply { code: 250, lines: vec!["Directory successfully changed.".to_string()] })));
+  assert_eq!(client.poll_event(), Some(Event::Error(FtpError::ServerError(421, "Timeout.".to_string()))));
+  assert!(client.is_idle());
+
+  // incomplete reply does not block the commands
+  let mut client = logged_in();
+  client.handle_input("421 Timeo".as_bytes());
+  assert_eq!(client.poll_event(), None);
+  assert!(client.is_idle());
+  client.handle_input("ut.\r\n".as_bytes());
+  assert_eq!(client.poll_event(), Some(Event::Error(FtpError::ServerError(421, "Timeout.".to_string()))));
+  client.send_pwd_req().unwrap();
+
+  // stray success reply does not close the client
+  let mut client = logged_in();
+  client.handle_input("226 Transfer complete.\r\n".as_bytes());
+  assert_eq!(client.poll_event(), Some(Event::Reply(Reply { code: 226, lines: vec!["Transfer complete.".to_string()] })));
+  assert!(client.is_idle());
+  client.send_cwd_req("/pub").unwrap();
+  client.handle_input("250 Directory successfully changed.\r\n".as_bytes());
+  assert_eq!(client.poll_event(), Some(Event::Reply(Reply { code: 250, lines: vec!["Directory successfully changed.".to_string()] })));
+}
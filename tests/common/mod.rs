@@ -0,0 +1,21 @@
+#![allow(dead_code)]
+
+use protocol_ftp_client::*;
+
+pub fn logged_in(tx_buff: &mut [u8], tx_count: &mut usize) -> FtpTransmitter {
+  logged_in_from(FtpReceiver::new(), tx_buff, tx_count)
+}
+
+pub fn logged_in_from(ftp_receiver: FtpReceiver, tx_buff: &mut [u8], tx_count: &mut usize) -> FtpTransmitter {
+  ftp_receiver
+    .try_advance("220 Service ready for new user.\r\n".as_bytes()).ok().unwrap()
+    .send_login(tx_buff, tx_count, "user").ok().unwrap()
+    .try_advance("331 User name okay, need password for user.\r\n".as_bytes()).ok().unwrap()
+    .send_password(tx_buff, tx_count, "secret").ok().unwrap()
+    .try_advance("230 User logged in, proceed.\r\n".as_bytes()).ok().unwrap()
+}
+
+/// Unwraps successfully parsed listing entries.
+pub fn files(list: Vec<Result<RemoteFile, FtpError>>) -> Vec<RemoteFile> {
+  list.into_iter().map(|file| file.unwrap()).collect()
+}